@@ -0,0 +1,375 @@
+//! Popisek u QR kódu – textový pruh pod kódem (hromadný export z CSV) nebo rámeček QR Platby.
+//!
+//! Textový pruh v rastru používá systémové písmo (Segoe UI / Arial / DejaVu Sans, případně
+//! soubor z proměnné `KJU_AR_FONT`), SVG jen `<text>` s bezpatkovým písmem. Nápis rámečku
+//! je pevný, a proto uložený jako obrys – písmo nepotřebuje a všude vypadá stejně.
+
+use crate::error::{Error, Result};
+use crate::shape::{PathCmd, KAPPA};
+use crate::style::QrStyle;
+use ab_glyph_rasterizer::{point, Rasterizer};
+use image::{imageops, GrayImage, Luma, Rgba, RgbaImage};
+use imageproc::drawing::{draw_text_mut, text_size};
+use rusttype::{Font, Scale};
+use std::sync::OnceLock;
+
+/// Výška pruhu s popiskem jako podíl šířky QR.
+pub(crate) const CAPTION_RATIO: f32 = 0.14;
+
+/// Popisek u samostatného QR.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Caption<'a> {
+    /// Pruh s textem pod kódem.
+    Text(&'a str),
+    /// Rámeček s nápisem „QR Platba“ v horní hraně podle grafického doporučení ČBA.
+    PaymentFrame,
+}
+
+const FONT_CANDIDATES: &[&str] = &[
+    r"C:\Windows\Fonts\segoeui.ttf",
+    r"C:\Windows\Fonts\arial.ttf",
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/truetype/liberation/LiberationSans-Regular.ttf",
+    "/System/Library/Fonts/Supplemental/Arial.ttf",
+    "/Library/Fonts/Arial.ttf",
+];
+
+fn caption_font() -> Result<&'static Font<'static>> {
+    static FONT: OnceLock<Option<Font<'static>>> = OnceLock::new();
+    FONT.get_or_init(|| {
+        let env = std::env::var("KJU_AR_FONT").ok();
+        env.iter()
+            .map(String::as_str)
+            .chain(FONT_CANDIDATES.iter().copied())
+            .filter_map(|p| std::fs::read(p).ok())
+            .find_map(Font::try_from_vec)
+    })
+    .as_ref()
+    .ok_or(Error::NoCaptionFont)
+}
+
+/// Výška pruhu s popiskem v px pro QR o šířce `width`.
+pub(crate) fn caption_height(width: u32) -> u32 {
+    ((width as f32 * CAPTION_RATIO).round() as u32).max(8)
+}
+
+/// Vykreslí pruh s vycentrovaným textem; písmo se zmenší, aby se text vešel na šířku.
+/// `bg = None` → průhledné pozadí.
+pub(crate) fn render_caption(
+    text: &str,
+    width: u32,
+    height: u32,
+    rgb: (u8, u8, u8),
+    bg: Option<(u8, u8, u8)>,
+    alpha: u8,
+) -> Result<RgbaImage> {
+    let font = caption_font()?;
+    let mut px = height as f32 * 0.7;
+    let (mut tw, _) = text_size(Scale::uniform(px), font, text);
+    let max_w = width as f32 * 0.92;
+    if tw as f32 > max_w && tw > 0 {
+        px *= max_w / tw as f32;
+        tw = text_size(Scale::uniform(px), font, text).0;
+    }
+    let scale = Scale::uniform(px);
+    let v = font.v_metrics(scale);
+    let text_h = v.ascent - v.descent;
+
+    let mut mask = GrayImage::new(width, height);
+    let x = (width as i32 - tw) / 2;
+    let y = ((height as f32 - text_h) / 2.0).round() as i32;
+    draw_text_mut(&mut mask, Luma([255]), x, y, scale, font, text);
+
+    let bg_rgba = match bg {
+        Some(c) => Rgba([c.0, c.1, c.2, alpha]),
+        None => Rgba([0, 0, 0, 0]),
+    };
+    let mut out = RgbaImage::from_pixel(width, height, bg_rgba);
+    for (p, m) in out.pixels_mut().zip(mask.pixels()) {
+        let cov = m[0] as u16;
+        if cov == 0 {
+            continue;
+        }
+        // text přes pozadí (obojí se stejnou průhledností)
+        let mix = |t: u8, b: u8| ((t as u16 * cov + b as u16 * (255 - cov)) / 255) as u8;
+        let (b, ba) = match bg {
+            Some(c) => ((c.0, c.1, c.2), alpha as u16),
+            None => (rgb, 0),
+        };
+        *p = Rgba([
+            mix(rgb.0, b.0),
+            mix(rgb.1, b.1),
+            mix(rgb.2, b.2),
+            ((alpha as u16 * cov + ba * (255 - cov)) / 255) as u8,
+        ]);
+    }
+    Ok(out)
+}
+
+/// Přidá ke QR popisek (barva textu i rámečku = barva modulů, pozadí podle stylu).
+pub fn add_caption(qr: &RgbaImage, caption: Caption, style: &QrStyle) -> Result<RgbaImage> {
+    let (w, h) = qr.dimensions();
+    let a = ((style.alpha_percent.min(100) as u16 * 255) / 100) as u8;
+    let Caption::Text(text) = caption else {
+        return Ok(add_payment_frame(qr, style.module_rgb, style.background_rgb, a));
+    };
+    let strip = render_caption(text, w, caption_height(w), style.module_rgb, style.background_rgb, a)?;
+    let mut out = RgbaImage::new(w, h + strip.height());
+    imageops::replace(&mut out, qr, 0, 0);
+    imageops::replace(&mut out, &strip, 0, h.into());
+    Ok(out)
+}
+
+/// Rozvržení rámečku QR Platby kolem QR o straně `side` (včetně tiché zóny).
+/// Čára i nápis jsou jeden obrys (y dolů, vyplnit nonzero); QR leží uvnitř s mezerou
+/// na všech stranách stejnou, aby nápis nezasahoval do tiché zóny.
+pub(crate) struct PaymentFrame {
+    pub width: f32,
+    pub height: f32,
+    pub qr_offset: (f32, f32), // levý horní roh QR
+    pub outline: Vec<PathCmd>,
+}
+
+impl PaymentFrame {
+    const LINE: f32 = 0.025; // tloušťka čáry (podíl strany QR)
+    const LABEL: f32 = 0.12; // velikost nápisu (výška řádku písma)
+    const LABEL_INDENT: f32 = 0.1; // nápis od levého okraje rámečku
+    const LABEL_GAP: f32 = 0.02; // přerušení čáry kolem nápisu
+
+    pub(crate) fn new(side: f32) -> Self {
+        let (t, h) = (Self::LINE * side, Self::LABEL * side);
+        // střed čáry = střed verzálek; nad čáru přesahují jen vyšší písmena
+        let line_y = (LABEL_TOP - LABEL_CAP_HEIGHT / 2.0) * h;
+        let baseline = line_y + LABEL_CAP_HEIGHT / 2.0 * h;
+        // pod nápisem ještě mezera na tloušťku čáry; stejně široká je i kolem ostatních stran
+        let gap = (baseline + LABEL_DESCENT * h - line_y - t / 2.0).max(0.0) + t;
+        let inset = t + gap;
+        let (width, height) = (side + 2.0 * inset, line_y + t / 2.0 + 2.0 * gap + side + t);
+        let qr_offset = (inset, line_y + t / 2.0 + gap);
+
+        let label_x = Self::LABEL_INDENT * side;
+        let (gap_l, gap_r) = (label_x - Self::LABEL_GAP * side, label_x + LABEL_WIDTH * h + Self::LABEL_GAP * side);
+        let mut outline = frame_outline((0.0, line_y - t / 2.0, width, height), t, (gap_l, gap_r));
+        outline.extend(LABEL_OUTLINE.iter().map(|c| c.map(|x, y| (label_x + x * h, baseline + y * h))));
+        Self { width, height, qr_offset, outline }
+    }
+}
+
+/// Rámeček QR Platby kolem hotového rastru QR; plocha mimo QR má barvu pozadí.
+fn add_payment_frame(qr: &RgbaImage, rgb: (u8, u8, u8), bg: Option<(u8, u8, u8)>, alpha: u8) -> RgbaImage {
+    let frame = PaymentFrame::new(qr.width() as f32);
+    let bg_rgba = match bg {
+        Some(c) => Rgba([c.0, c.1, c.2, alpha]),
+        None => Rgba([0, 0, 0, 0]),
+    };
+    let mut out = RgbaImage::from_pixel(frame.width.ceil() as u32, frame.height.ceil() as u32, bg_rgba);
+    let (x, y) = frame.qr_offset;
+    imageops::replace(&mut out, qr, x.round() as i64, y.round() as i64);
+    fill_outline(&mut out, &frame.outline, rgb, alpha);
+    out
+}
+
+/// Vyplní obrys (nonzero) s vyhlazením hran; barva se skládá přes obsah obrázku.
+fn fill_outline(img: &mut RgbaImage, outline: &[PathCmd], rgb: (u8, u8, u8), alpha: u8) {
+    let mut r = Rasterizer::new(img.width() as usize, img.height() as usize);
+    let (mut start, mut last) = (point(0.0, 0.0), point(0.0, 0.0));
+    for cmd in outline {
+        match *cmd {
+            PathCmd::Move(x, y) => {
+                start = point(x, y);
+                last = start;
+            }
+            PathCmd::Line(x, y) => {
+                r.draw_line(last, point(x, y));
+                last = point(x, y);
+            }
+            PathCmd::Cubic([x1, y1, x2, y2, x, y]) => {
+                r.draw_cubic(last, point(x1, y1), point(x2, y2), point(x, y));
+                last = point(x, y);
+            }
+            PathCmd::Close => {
+                r.draw_line(last, start);
+                last = start;
+            }
+        }
+    }
+    r.for_each_pixel_2d(|x, y, cov| {
+        let sa = alpha as f32 / 255.0 * cov.min(1.0);
+        if sa <= 0.0 {
+            return;
+        }
+        let p = img.get_pixel_mut(x, y);
+        let da = p[3] as f32 / 255.0 * (1.0 - sa);
+        let out_a = sa + da;
+        let mix = |s: u8, d: u8| ((s as f32 * sa + d as f32 * da) / out_a).round() as u8;
+        *p = Rgba([mix(rgb.0, p[0]), mix(rgb.1, p[1]), mix(rgb.2, p[2]), (out_a * 255.0).round() as u8]);
+    });
+}
+
+/// Obrys rámečku `(x0, y0, x1, y1)` o tloušťce `t` se zaoblenými rohy, přerušený
+/// v horní hraně mezi `gap.0` a `gap.1` – jeden uzavřený útvar tvaru „C“.
+fn frame_outline((x0, y0, x1, y1): (f32, f32, f32, f32), t: f32, gap: (f32, f32)) -> Vec<PathCmd> {
+    let (r, ri) = (2.0 * t, t); // vnější a vnitřní poloměr rohu
+    let (xi0, yi0, xi1, yi1) = (x0 + t, y0 + t, x1 - t, y1 - t);
+    // čtvrtkruh z `a` do `b` kolem ostrého rohu `c`
+    let arc = |a: (f32, f32), c: (f32, f32), b: (f32, f32)| {
+        let k = |p: (f32, f32)| (p.0 + KAPPA * (c.0 - p.0), p.1 + KAPPA * (c.1 - p.1));
+        let (k1, k2) = (k(a), k(b));
+        [PathCmd::Line(a.0, a.1), PathCmd::Cubic([k1.0, k1.1, k2.0, k2.1, b.0, b.1])]
+    };
+    let mut p = vec![PathCmd::Move(gap.1, y0)];
+    // vnější hrana po směru hodinových ručiček
+    p.extend(arc((x1 - r, y0), (x1, y0), (x1, y0 + r)));
+    p.extend(arc((x1, y1 - r), (x1, y1), (x1 - r, y1)));
+    p.extend(arc((x0 + r, y1), (x0, y1), (x0, y1 - r)));
+    p.extend(arc((x0, y0 + r), (x0, y0), (x0 + r, y0)));
+    p.push(PathCmd::Line(gap.0, y0));
+    // vnitřní hrana zpět proti směru
+    p.push(PathCmd::Line(gap.0, yi0));
+    p.extend(arc((xi0 + ri, yi0), (xi0, yi0), (xi0, yi0 + ri)));
+    p.extend(arc((xi0, yi1 - ri), (xi0, yi1), (xi0 + ri, yi1)));
+    p.extend(arc((xi1 - ri, yi1), (xi1, yi1), (xi1, yi1 - ri)));
+    p.extend(arc((xi1, yi0 + ri), (xi1, yi0), (xi1 - ri, yi0)));
+    p.push(PathCmd::Line(gap.1, yi0));
+    p.push(PathCmd::Close);
+    p
+}
+
+// Míry nápisu v jednotkách výšky řádku písma (účaří y = 0, y dolů).
+const LABEL_WIDTH: f32 = 4.689;
+const LABEL_TOP: f32 = 0.653; // nejvyšší bod („l“, „t“)
+const LABEL_CAP_HEIGHT: f32 = 0.63;
+const LABEL_DESCENT: f32 = 0.125; // ocásek „Q“
+
+/// Obrys nápisu „QR Platba“ (DejaVu Sans Bold, volná licence Bitstream Vera).
+const LABEL_OUTLINE: &[PathCmd] = {
+    use PathCmd::{Close as Z, Cubic as C, Line as L, Move as M};
+    &[
+        M(0.339, 0.011), L(0.327, 0.011), C([0.223, 0.011, 0.143, -0.017, 0.086, -0.074]),
+        C([0.028, -0.131, 0.0, -0.211, 0.0, -0.313]), C([0.0, -0.414, 0.028, -0.494, 0.086, -0.551]),
+        C([0.143, -0.609, 0.222, -0.638, 0.322, -0.638]), C([0.423, -0.638, 0.503, -0.609, 0.559, -0.552]),
+        C([0.616, -0.495, 0.644, -0.415, 0.644, -0.313]), C([0.644, -0.242, 0.629, -0.181, 0.599, -0.13]),
+        C([0.569, -0.08, 0.526, -0.041, 0.469, -0.016]), L(0.595, 0.125), L(0.441, 0.125), L(0.339, 0.011), Z,
+        M(0.322, -0.521), C([0.273, -0.521, 0.235, -0.502, 0.207, -0.466]),
+        C([0.18, -0.43, 0.167, -0.378, 0.167, -0.313]), C([0.167, -0.245, 0.18, -0.194, 0.207, -0.158]),
+        C([0.233, -0.123, 0.272, -0.105, 0.322, -0.105]), C([0.371, -0.105, 0.41, -0.123, 0.437, -0.159]),
+        C([0.464, -0.196, 0.478, -0.247, 0.478, -0.313]), C([0.478, -0.378, 0.464, -0.43, 0.437, -0.466]),
+        C([0.41, -0.502, 0.371, -0.521, 0.322, -0.521]), Z, M(0.996, -0.349),
+        C([1.029, -0.349, 1.054, -0.355, 1.068, -0.367]), C([1.083, -0.38, 1.09, -0.401, 1.09, -0.43]),
+        C([1.09, -0.458, 1.083, -0.478, 1.068, -0.491]), C([1.054, -0.503, 1.029, -0.509, 0.996, -0.509]),
+        L(0.928, -0.509), L(0.928, -0.349), L(0.996, -0.349), Z, M(0.928, -0.237), L(0.928, 0.0), L(0.766, 0.0),
+        L(0.766, -0.626), L(1.013, -0.626), C([1.095, -0.626, 1.156, -0.612, 1.194, -0.585]),
+        C([1.233, -0.557, 1.252, -0.513, 1.252, -0.453]), C([1.252, -0.412, 1.242, -0.378, 1.222, -0.352]),
+        C([1.202, -0.325, 1.172, -0.305, 1.131, -0.293]), C([1.154, -0.288, 1.173, -0.276, 1.191, -0.259]),
+        C([1.208, -0.241, 1.226, -0.214, 1.244, -0.178]), L(1.332, 0.0), L(1.16, 0.0), L(1.083, -0.156),
+        C([1.068, -0.187, 1.052, -0.208, 1.036, -0.22]), C([1.021, -0.231, 1.0, -0.237, 0.973, -0.237]),
+        L(0.928, -0.237), Z, M(1.727, -0.626), L(1.995, -0.626), C([2.074, -0.626, 2.136, -0.609, 2.178, -0.573]),
+        C([2.221, -0.538, 2.242, -0.487, 2.242, -0.422]), C([2.242, -0.356, 2.221, -0.306, 2.178, -0.27]),
+        C([2.136, -0.235, 2.074, -0.217, 1.995, -0.217]), L(1.888, -0.217), L(1.888, 0.0), L(1.727, 0.0),
+        L(1.727, -0.626), Z, M(1.888, -0.509), L(1.888, -0.334), L(1.978, -0.334),
+        C([2.009, -0.334, 2.033, -0.342, 2.05, -0.357]), C([2.067, -0.372, 2.076, -0.394, 2.076, -0.422]),
+        C([2.076, -0.45, 2.067, -0.471, 2.05, -0.487]), C([2.033, -0.502, 2.009, -0.509, 1.978, -0.509]),
+        L(1.888, -0.509), Z, M(2.35, -0.653), L(2.5, -0.653), L(2.5, 0.0), L(2.35, 0.0), L(2.35, -0.653), Z,
+        M(2.855, -0.211), C([2.823, -0.211, 2.8, -0.206, 2.784, -0.195]),
+        C([2.768, -0.185, 2.76, -0.169, 2.76, -0.148]), C([2.76, -0.129, 2.767, -0.115, 2.779, -0.104]),
+        C([2.792, -0.093, 2.81, -0.088, 2.832, -0.088]), C([2.861, -0.088, 2.884, -0.098, 2.904, -0.118]),
+        C([2.923, -0.138, 2.933, -0.164, 2.933, -0.194]), L(2.933, -0.211), L(2.855, -0.211), Z, M(3.084, -0.268),
+        L(3.084, 0.0), L(2.933, 0.0), L(2.933, -0.07), C([2.913, -0.041, 2.89, -0.02, 2.865, -0.007]),
+        C([2.84, 0.006, 2.809, 0.012, 2.773, 0.012]), C([2.724, 0.012, 2.685, -0.002, 2.654, -0.03]),
+        C([2.624, -0.059, 2.609, -0.096, 2.609, -0.141]), C([2.609, -0.196, 2.628, -0.236, 2.666, -0.262]),
+        C([2.704, -0.288, 2.763, -0.301, 2.844, -0.301]), L(2.933, -0.301), L(2.933, -0.313),
+        C([2.933, -0.336, 2.923, -0.354, 2.905, -0.365]), C([2.886, -0.376, 2.857, -0.381, 2.817, -0.381]),
+        C([2.785, -0.381, 2.755, -0.378, 2.727, -0.372]), C([2.699, -0.365, 2.674, -0.356, 2.65, -0.343]),
+        L(2.65, -0.457), C([2.682, -0.465, 2.714, -0.471, 2.747, -0.475]),
+        C([2.779, -0.479, 2.812, -0.481, 2.844, -0.481]), C([2.929, -0.481, 2.99, -0.464, 3.028, -0.431]),
+        C([3.065, -0.398, 3.084, -0.343, 3.084, -0.268]), Z, M(3.388, -0.603), L(3.388, -0.47), L(3.543, -0.47),
+        L(3.543, -0.362), L(3.388, -0.362), L(3.388, -0.163), C([3.388, -0.141, 3.392, -0.127, 3.401, -0.119]),
+        C([3.409, -0.111, 3.427, -0.107, 3.452, -0.107]), L(3.53, -0.107), L(3.53, 0.0), L(3.401, 0.0),
+        C([3.342, 0.0, 3.299, -0.012, 3.275, -0.037]), C([3.25, -0.062, 3.238, -0.104, 3.238, -0.163]),
+        L(3.238, -0.362), L(3.163, -0.362), L(3.163, -0.47), L(3.238, -0.47), L(3.238, -0.603), L(3.388, -0.603), Z,
+        M(3.884, -0.097), C([3.917, -0.097, 3.941, -0.109, 3.958, -0.132]),
+        C([3.975, -0.156, 3.983, -0.19, 3.983, -0.234]), C([3.983, -0.279, 3.975, -0.313, 3.958, -0.337]),
+        C([3.941, -0.36, 3.917, -0.372, 3.884, -0.372]), C([3.852, -0.372, 3.828, -0.36, 3.81, -0.337]),
+        C([3.793, -0.313, 3.785, -0.279, 3.785, -0.234]), C([3.785, -0.19, 3.793, -0.156, 3.81, -0.132]),
+        C([3.828, -0.109, 3.852, -0.097, 3.884, -0.097]), Z, M(3.785, -0.401),
+        C([3.805, -0.428, 3.828, -0.449, 3.853, -0.462]), C([3.879, -0.475, 3.908, -0.481, 3.94, -0.481]),
+        C([3.998, -0.481, 4.046, -0.458, 4.083, -0.412]), C([4.12, -0.366, 4.139, -0.307, 4.139, -0.234]),
+        C([4.139, -0.162, 4.12, -0.103, 4.083, -0.057]), C([4.046, -0.011, 3.998, 0.012, 3.94, 0.012]),
+        C([3.908, 0.012, 3.879, 0.006, 3.853, -0.007]), C([3.828, -0.02, 3.805, -0.041, 3.785, -0.068]),
+        L(3.785, 0.0), L(3.634, 0.0), L(3.634, -0.653), L(3.785, -0.653), L(3.785, -0.401), Z, M(4.46, -0.211),
+        C([4.429, -0.211, 4.405, -0.206, 4.389, -0.195]), C([4.373, -0.185, 4.366, -0.169, 4.366, -0.148]),
+        C([4.366, -0.129, 4.372, -0.115, 4.385, -0.104]), C([4.397, -0.093, 4.415, -0.088, 4.438, -0.088]),
+        C([4.466, -0.088, 4.49, -0.098, 4.509, -0.118]), C([4.528, -0.138, 4.538, -0.164, 4.538, -0.194]),
+        L(4.538, -0.211), L(4.46, -0.211), Z, M(4.689, -0.268), L(4.689, 0.0), L(4.538, 0.0), L(4.538, -0.07),
+        C([4.518, -0.041, 4.495, -0.02, 4.47, -0.007]), C([4.445, 0.006, 4.414, 0.012, 4.378, 0.012]),
+        C([4.329, 0.012, 4.29, -0.002, 4.26, -0.03]), C([4.229, -0.059, 4.214, -0.096, 4.214, -0.141]),
+        C([4.214, -0.196, 4.233, -0.236, 4.271, -0.262]), C([4.309, -0.288, 4.368, -0.301, 4.449, -0.301]),
+        L(4.538, -0.301), L(4.538, -0.313), C([4.538, -0.336, 4.529, -0.354, 4.51, -0.365]),
+        C([4.491, -0.376, 4.462, -0.381, 4.422, -0.381]), C([4.39, -0.381, 4.36, -0.378, 4.332, -0.372]),
+        C([4.305, -0.365, 4.279, -0.356, 4.255, -0.343]), L(4.255, -0.457),
+        C([4.287, -0.465, 4.32, -0.471, 4.352, -0.475]), C([4.385, -0.479, 4.417, -0.481, 4.449, -0.481]),
+        C([4.534, -0.481, 4.595, -0.464, 4.633, -0.431]), C([4.671, -0.398, 4.689, -0.343, 4.689, -0.268]), Z,
+    ]
+};
+
+/// Escapování textu pro XML (SVG).
+pub(crate) fn xml_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ohraničení obrysu (x0, y0, x1, y1).
+    fn bounds(cmds: &[PathCmd]) -> (f32, f32, f32, f32) {
+        let pts = cmds.iter().flat_map(|c| match *c {
+            PathCmd::Move(x, y) | PathCmd::Line(x, y) => vec![(x, y)],
+            PathCmd::Cubic(k) => vec![(k[0], k[1]), (k[2], k[3]), (k[4], k[5])],
+            PathCmd::Close => vec![],
+        });
+        pts.fold((f32::MAX, f32::MAX, f32::MIN, f32::MIN), |b, (x, y)| (b.0.min(x), b.1.min(y), b.2.max(x), b.3.max(y)))
+    }
+
+    #[test]
+    fn frame_keeps_clear_of_qr() {
+        let f = PaymentFrame::new(100.0);
+        let (qx, qy) = f.qr_offset;
+        assert!(qx > 0.0 && qy > 0.0);
+        assert!((f.width - (100.0 + 2.0 * qx)).abs() < 1e-3);
+        assert!(f.height > qy + 100.0);
+        // nápis leží nad QR a celý obrys se vejde na stránku
+        let label = bounds(&LABEL_OUTLINE.iter().map(|c| c.map(|x, y| (x * 12.0, y * 12.0))).collect::<Vec<_>>());
+        assert!(label.2 - label.0 <= LABEL_WIDTH * 12.0 + 1e-3);
+        let all = bounds(&f.outline);
+        assert!(all.0 >= -1e-3 && all.1 >= -1e-3 && all.2 <= f.width + 1e-3 && all.3 <= f.height + 1e-3, "{all:?}");
+    }
+
+    #[test]
+    fn frame_around_raster() {
+        let qr = RgbaImage::from_fn(120, 120, |x, y| if (x / 10 + y / 10) % 2 == 0 { Rgba([0, 0, 0, 255]) } else { Rgba([255; 4]) });
+        let style = QrStyle { alpha_percent: 100, background_rgb: Some((255, 255, 255)), ..QrStyle::default() };
+        let out = add_caption(&qr, Caption::PaymentFrame, &style).unwrap();
+        let f = PaymentFrame::new(120.0);
+        assert_eq!(out.dimensions(), (f.width.ceil() as u32, f.height.ceil() as u32));
+        // QR se zkopíruje beze změny – rámeček ani nápis do něj nezasahují
+        let (ox, oy) = (f.qr_offset.0.round() as u32, f.qr_offset.1.round() as u32);
+        assert!(qr.enumerate_pixels().all(|(x, y, p)| out.get_pixel(x + ox, y + oy) == p));
+        // čára rámečku v barvě modulů uprostřed levé hrany, nápis přeruší horní hranu
+        let line = (PaymentFrame::LINE * 120.0 / 2.0) as u32;
+        assert_eq!(out.get_pixel(line, out.height() / 2), &Rgba([0, 0, 0, 255]));
+        assert!(out.pixels().filter(|p| p[0] < 128).count() > qr.pixels().filter(|p| p[0] < 128).count());
+    }
+}
@@ -0,0 +1,446 @@
+//! Příkazová řádka – generování QR bez GUI (skripty, build pipeline).
+//! Bez podpříkazu se spustí běžné okno aplikace.
+
+use anyhow::Context;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use kju_ar::{
+    default_bulk_dir, default_out_path, default_qr_out_path, export_bulk_with_progress, load_logo, overlay_into_image,
+    parse_url_lines, raster_size_px, save_qr, verify_overlay, verify_qr_file, BulkItem, BulkOptions, ColumnMapping, Corner, EcLevel,
+    EyeShape, EyeStyle, Gradient, GradientKind, ModuleShape, OutputFormat, OverlaySpec, QrStyle, Table, Utm,
+};
+use std::io::Read;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(name = "kju-ar", version, about = "Kjů ár – QR kódy do obrázku nebo samostatně. Bez příkazu se otevře GUI.")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Uloží jeden samostatný QR kód
+    Single {
+        /// Text / URL pro QR kód
+        url: String,
+        /// Výstupní soubor (formát podle přípony; výchozí qr.png)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Formát výstupu (png, jpg, tif, svg, pdf), pokud nejde odvodit z přípony;
+        /// jiná známá přípona u `-o` je chyba
+        #[arg(short, long, value_parser = parse_format)]
+        format: Option<OutputFormat>,
+        /// Uložený QR zpětně přečíst a selhat, pokud nejde dekódovat
+        #[arg(long)]
+        verify: bool,
+        #[command(flatten)]
+        style: StyleArgs,
+        #[command(flatten)]
+        utm: UtmArgs,
+    },
+    /// Hromadně uloží samostatné QR kódy – URL po řádcích nebo tabulka CSV/TSV
+    Bulk {
+        /// Soubor s URL po řádcích („-“ = standardní vstup); .csv/.tsv se čte jako tabulka
+        #[arg(default_value = "-")]
+        input: PathBuf,
+        /// Číst vstup jako tabulku i bez přípony .csv/.tsv; sloupce se určí podle záhlaví
+        /// (url, file, color, background, size, caption, utm_*), bez záhlaví 1. = obsah, 2. = název souboru
+        #[arg(long)]
+        table: bool,
+        /// Výstupní složka (výchozí qr_export)
+        #[arg(short = 'd', long)]
+        out_dir: Option<PathBuf>,
+        /// Formát výstupu (png, jpg, tif, svg, pdf)
+        #[arg(short, long, default_value = "png", value_parser = parse_format)]
+        format: OutputFormat,
+        /// Uloží výsledek po položkách (OK/chyba a důvod) do CSV
+        #[arg(long)]
+        report: Option<PathBuf>,
+        /// Počet vláken pro vykreslování (0 = všechna jádra)
+        #[arg(short = 'j', long, default_value_t = 0)]
+        jobs: usize,
+        /// Uložený QR zpětně přečíst a selhat, pokud nejde dekódovat
+        #[arg(long)]
+        verify: bool,
+        #[command(flatten)]
+        style: StyleArgs,
+        #[command(flatten)]
+        utm: UtmArgs,
+    },
+    /// Vloží QR kód do zdrojového obrázku
+    Overlay {
+        /// Text / URL pro QR kód
+        url: String,
+        /// Zdrojový obrázek
+        #[arg(short, long)]
+        input: PathBuf,
+        /// Výstupní soubor (výchozí out_<původní>.<přípona>)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Roh pro umístění: se, sw, ne, nw, custom (X/Y od levého-horního rohu)
+        #[arg(long, default_value = "se", value_parser = parse_corner)]
+        corner: Corner,
+        /// Odsazení od kraje v px (u custom souřadnice X)
+        #[arg(long, default_value_t = 10)]
+        dx: i32,
+        /// Odsazení od kraje v px (u custom souřadnice Y)
+        #[arg(long, default_value_t = 10)]
+        dy: i32,
+        /// Uložený QR zpětně přečíst a selhat, pokud nejde dekódovat
+        #[arg(long)]
+        verify: bool,
+        #[command(flatten)]
+        style: StyleArgs,
+        #[command(flatten)]
+        utm: UtmArgs,
+    },
+}
+
+/// Vzhled QR – stejné volby jako ve skupině „QR kód“ v GUI.
+#[derive(Args)]
+pub struct StyleArgs {
+    /// Velikost QR v px
+    #[arg(long, default_value_t = 160, value_parser = clap::value_parser!(u32).range(64..=2048))]
+    size: u32,
+    /// Úroveň korekce chyb (L, M, Q, H)
+    #[arg(long, default_value = "M", value_parser = parse_ec)]
+    ec: EcLevel,
+    /// Barva modulů (#RRGGBB)
+    #[arg(long, default_value = "#000000", value_parser = parse_rgb)]
+    color: (u8, u8, u8),
+    /// Přechod barvy modulů – dvě a více barev oddělených čárkou (#000000,#1d4ed8)
+    #[arg(long, value_parser = parse_gradient_colors)]
+    gradient: Option<GradientColors>,
+    /// Druh přechodu
+    #[arg(long, value_enum, default_value_t = GradientType::Linear)]
+    gradient_type: GradientType,
+    /// Úhel lineárního přechodu ve stupních (0 = zleva doprava, 90 = shora dolů)
+    #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+    gradient_angle: f32,
+    /// Barva pozadí (#RRGGBB); bez ní je pozadí průhledné
+    #[arg(long, value_parser = parse_rgb)]
+    background: Option<(u8, u8, u8)>,
+    /// Krytí QR v % (100 = neprůhledný)
+    #[arg(long, default_value_t = 85, value_parser = clap::value_parser!(u8).range(0..=100))]
+    alpha: u8,
+    /// Zaoblení rohů modulů v % modulu
+    #[arg(long, default_value_t = 0, value_parser = clap::value_parser!(u8).range(0..=50))]
+    rounding: u8,
+    /// Tvar modulů: square, dot, diamond, vbars, hbars, liquid
+    #[arg(long, default_value = "square", value_parser = parse_shape)]
+    shape: ModuleShape,
+    /// Tvar rámečku rohových značek: square, rounded, circle, leaf
+    #[arg(long, value_parser = parse_eye_shape)]
+    eye_frame: Option<EyeShape>,
+    /// Tvar středu rohových značek: square, rounded, circle, leaf
+    #[arg(long, value_parser = parse_eye_shape)]
+    eye_pupil: Option<EyeShape>,
+    /// Barva rámečku rohových značek (#RRGGBB; výchozí barva modulů)
+    #[arg(long, value_parser = parse_rgb)]
+    eye_frame_color: Option<(u8, u8, u8)>,
+    /// Barva středu rohových značek (#RRGGBB; výchozí barva modulů)
+    #[arg(long, value_parser = parse_rgb)]
+    eye_pupil_color: Option<(u8, u8, u8)>,
+    /// Logo uprostřed QR
+    #[arg(long)]
+    logo: Option<PathBuf>,
+    /// Velikost loga v % šířky symbolu
+    #[arg(long, default_value_t = 20, value_parser = clap::value_parser!(u8).range(10..=30))]
+    logo_size: u8,
+    /// Nevyčišťovat moduly pod logem
+    #[arg(long)]
+    no_knockout: bool,
+    /// Tichá zóna (světlý okraj) kolem symbolu v modulech; pod 2 hrozí nečitelnost
+    #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u32).range(0..=10))]
+    quiet_zone: u32,
+    /// Fyzická velikost QR v PDF (mm)
+    #[arg(long, default_value_t = 50.0)]
+    pdf_size_mm: f32,
+    /// Fyzická velikost QR i s tichou zónou, např. 25mm nebo 1in (přepíše --pdf-size-mm)
+    #[arg(long, value_parser = parse_length_mm)]
+    print_size: Option<f32>,
+    /// Rozlišení pro tisk; velikost v px se pak odvodí z fyzické velikosti (celé px na modul)
+    /// a DPI se zapíše do PNG/TIFF/JPG
+    #[arg(long, value_parser = clap::value_parser!(u32).range(72..=2400))]
+    dpi: Option<u32>,
+}
+
+impl StyleArgs {
+    /// Styl z voleb; upozornění (kontrast, úzká tichá zóna) vypíše na stderr, export nezastaví.
+    fn qr_style(&self) -> QrStyle {
+        let style = QrStyle {
+            size_px: self.size,
+            module_rgb: self.color,
+            gradient: self.gradient(),
+            background_rgb: self.background,
+            alpha_percent: self.alpha,
+            rounding_percent: self.rounding,
+            module_shape: self.shape,
+            eyes: self.eyes(),
+            ec_level: self.ec,
+            print_size_mm: self.print_size.unwrap_or(self.pdf_size_mm).clamp(5.0, 1000.0),
+            quiet_zone_mod: self.quiet_zone,
+            dpi: self.dpi,
+        };
+        for w in style.warnings() {
+            eprintln!("Varování: {w}");
+        }
+        style
+    }
+
+    fn gradient(&self) -> Option<Gradient> {
+        let colors = self.gradient.as_ref()?;
+        let kind = match self.gradient_type {
+            GradientType::Linear => GradientKind::Linear { angle_deg: self.gradient_angle },
+            GradientType::Radial => GradientKind::Radial,
+        };
+        Some(Gradient::even(kind, &colors.0))
+    }
+
+    /// Vlastní oka, pokud je zadaná aspoň jedna z voleb `--eye-*`.
+    fn eyes(&self) -> Option<EyeStyle> {
+        if self.eye_frame.is_none() && self.eye_pupil.is_none() && self.eye_frame_color.is_none() && self.eye_pupil_color.is_none() {
+            return None;
+        }
+        Some(EyeStyle {
+            frame: self.eye_frame.unwrap_or_default(),
+            pupil: self.eye_pupil.unwrap_or_default(),
+            frame_rgb: self.eye_frame_color.unwrap_or(self.color),
+            pupil_rgb: self.eye_pupil_color.unwrap_or(self.color),
+        })
+    }
+}
+
+/// Druh přechodu pro `--gradient-type`; úhel se bere z `--gradient-angle`.
+#[derive(Clone, Copy, ValueEnum)]
+enum GradientType {
+    Linear,
+    Radial,
+}
+
+/// Parametry kampaně připojené k odkazu http(s); stávající dotaz zůstane.
+#[derive(Args)]
+pub struct UtmArgs {
+    /// utm_source (zdroj, např. plakat)
+    #[arg(long)]
+    utm_source: Option<String>,
+    /// utm_medium (kanál, např. print)
+    #[arg(long)]
+    utm_medium: Option<String>,
+    /// utm_campaign (název kampaně)
+    #[arg(long)]
+    utm_campaign: Option<String>,
+    /// utm_term (klíčové slovo)
+    #[arg(long)]
+    utm_term: Option<String>,
+    /// utm_content (varianta obsahu)
+    #[arg(long)]
+    utm_content: Option<String>,
+}
+
+impl UtmArgs {
+    fn utm(self) -> Utm {
+        Utm {
+            source: self.utm_source.unwrap_or_default(),
+            medium: self.utm_medium.unwrap_or_default(),
+            campaign: self.utm_campaign.unwrap_or_default(),
+            term: self.utm_term.unwrap_or_default(),
+            content: self.utm_content.unwrap_or_default(),
+        }
+    }
+}
+
+/// Provede podpříkaz; cesty k uloženým souborům vypíše na stdout.
+pub fn run(cmd: Command) -> anyhow::Result<()> {
+    match cmd {
+        Command::Single { url, output, format, verify, style, utm } => {
+            if url.trim().is_empty() {
+                anyhow::bail!("URL je prázdná");
+            }
+            let url = &utm.utm().apply(&url);
+            let outp = match (output, format) {
+                (Some(p), _) => p,
+                (None, Some(f)) => default_qr_out_path().with_extension(f.ext()),
+                (None, None) => default_qr_out_path(),
+            };
+            // formát z přípony má přednost jako v GUI; `--format` jen tam, kde přípona nic neříká
+            let fmt = match (OutputFormat::from_path(&outp), format) {
+                (Some(ext), Some(f)) if ext != f => anyhow::bail!(
+                    "přípona souboru {} neodpovídá formátu {} – změňte příponu nebo vynechte --format",
+                    outp.display(),
+                    f.ext()
+                ),
+                (Some(ext), _) => ext,
+                (None, f) => f.unwrap_or(OutputFormat::Png),
+            };
+            let logo = load_logo(style.logo.as_deref(), style.logo_size, !style.no_knockout)?;
+            let qr_style = style.qr_style();
+            save_qr(url, &qr_style, logo.as_ref(), None, &outp, fmt)?;
+            println!("{}", outp.display());
+            if let Some(dpi) = qr_style.dpi
+                && matches!(fmt, OutputFormat::Png | OutputFormat::Jpeg | OutputFormat::Tiff)
+            {
+                // skutečný rozměr po zaokrouhlení na celé pixely na modul
+                let px = raster_size_px(url, &qr_style, logo.as_ref())?;
+                eprintln!("{px} px při {dpi} dpi = {:.2} mm", px as f32 / dpi as f32 * 25.4);
+            }
+            if verify {
+                verify_qr_file(url, &outp, fmt)?;
+            }
+        }
+        Command::Bulk { input, table, out_dir, format, report: report_path, jobs, verify, style, utm } => {
+            let text = if input.as_os_str() == "-" {
+                let mut s = String::new();
+                std::io::stdin().read_to_string(&mut s).context("Nejde číst standardní vstup")?;
+                s
+            } else {
+                std::fs::read_to_string(&input)
+                    .with_context(|| format!("Nejde číst soubor: {}", input.display()))?
+            };
+            let is_table = table
+                || input
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| e.eq_ignore_ascii_case("csv") || e.eq_ignore_ascii_case("tsv"));
+            let items = if is_table {
+                let t = Table::parse(text.as_bytes())
+                    .with_context(|| format!("Nejde číst tabulku: {}", input.display()))?;
+                ColumnMapping::guess(&t).items(&t)?
+            } else {
+                parse_url_lines(&text).into_iter().map(BulkItem::new).collect()
+            };
+            let dir = out_dir.unwrap_or_else(default_bulk_dir);
+            let logo = load_logo(style.logo.as_deref(), style.logo_size, !style.no_knockout)?;
+            let options = BulkOptions { workers: jobs, verify, utm: utm.utm() };
+            let report =
+                export_bulk_with_progress(&items, &dir, &style.qr_style(), logo.as_ref(), format, options, |_| true)?;
+            for o in &report.outcomes {
+                if let Some(e) = &o.error {
+                    eprintln!("Chyba: {}. {}: {e}", o.index1, o.payload);
+                }
+                if let Some(e) = &o.unreadable {
+                    eprintln!("Nečitelný: {}. {}: {e}", o.index1, o.payload);
+                }
+            }
+            if let Some(p) = &report_path {
+                report.write_csv(p)?;
+            }
+            println!("Hotovo: {} souborů do {}", report.ok_count(), dir.display());
+            if report.failed_count() > 0 {
+                anyhow::bail!("{} položek selhalo", report.failed_count());
+            }
+            if report.unreadable_count() > 0 {
+                anyhow::bail!("{} uložených QR nejde přečíst", report.unreadable_count());
+            }
+        }
+        Command::Overlay { url, input, output, corner, dx, dy, verify, style, utm } => {
+            if url.trim().is_empty() {
+                anyhow::bail!("URL je prázdná");
+            }
+            let url = &utm.utm().apply(&url);
+            let outp = output.unwrap_or_else(|| default_out_path(Some(&input)));
+            let logo = load_logo(style.logo.as_deref(), style.logo_size, !style.no_knockout)?;
+            let spec = OverlaySpec { corner, offset_x: dx, offset_y: dy };
+            let qr_style = style.qr_style();
+            overlay_into_image(url, &qr_style, logo.as_ref(), &input, spec, &outp)?;
+            println!("{}", outp.display());
+            if verify {
+                verify_overlay(url, &input, &outp)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Release build na Windows nemá vlastní konzoli – připojí se ke konzoli,
+/// ze které byl program spuštěn, aby byl vidět výstup a chyby.
+pub fn attach_parent_console() {
+    #[cfg(all(target_os = "windows", not(debug_assertions)))]
+    unsafe {
+        use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+fn parse_format(s: &str) -> Result<OutputFormat, String> {
+    OutputFormat::from_ext(s)
+        .ok_or_else(|| format!("neznámý formát „{s}“ (png, jpg, tif, svg, pdf)"))
+}
+
+fn parse_corner(s: &str) -> Result<Corner, String> {
+    match s.to_ascii_lowercase().as_str() {
+        "se" => Ok(Corner::Southeast),
+        "sw" => Ok(Corner::Southwest),
+        "ne" => Ok(Corner::Northeast),
+        "nw" => Ok(Corner::Northwest),
+        "custom" => Ok(Corner::Custom),
+        _ => Err(format!("neznámý roh „{s}“ (se, sw, ne, nw, custom)")),
+    }
+}
+
+fn parse_shape(s: &str) -> Result<ModuleShape, String> {
+    match s.to_ascii_lowercase().as_str() {
+        "square" => Ok(ModuleShape::Square),
+        "dot" => Ok(ModuleShape::Dot),
+        "diamond" => Ok(ModuleShape::Diamond),
+        "vbars" => Ok(ModuleShape::VerticalBars),
+        "hbars" => Ok(ModuleShape::HorizontalBars),
+        "liquid" => Ok(ModuleShape::Liquid),
+        _ => Err(format!("neznámý tvar „{s}“ (square, dot, diamond, vbars, hbars, liquid)")),
+    }
+}
+
+/// Délka s jednotkou mm, cm nebo in (bez jednotky mm) převedená na mm.
+fn parse_length_mm(s: &str) -> Result<f32, String> {
+    let t = s.trim().to_ascii_lowercase();
+    let (num, k) = if let Some(n) = t.strip_suffix("mm") {
+        (n, 1.0)
+    } else if let Some(n) = t.strip_suffix("cm") {
+        (n, 10.0)
+    } else if let Some(n) = t.strip_suffix("in").or_else(|| t.strip_suffix('"')) {
+        (n, 25.4)
+    } else {
+        (t.as_str(), 1.0)
+    };
+    match num.trim().replace(',', ".").parse::<f32>() {
+        Ok(v) if v > 0.0 => Ok(v * k),
+        _ => Err(format!("neplatná délka „{s}“ (např. 25mm, 2.5cm, 1in)")),
+    }
+}
+
+/// Barvy přechodu z `--gradient` (aspoň dvě).
+#[derive(Clone)]
+struct GradientColors(Vec<(u8, u8, u8)>);
+
+fn parse_gradient_colors(s: &str) -> Result<GradientColors, String> {
+    let colors = s.split(',').map(parse_rgb).collect::<Result<Vec<_>, _>>()?;
+    if colors.len() < 2 {
+        return Err("přechod potřebuje aspoň dvě barvy oddělené čárkou".into());
+    }
+    Ok(GradientColors(colors))
+}
+
+fn parse_eye_shape(s: &str) -> Result<EyeShape, String> {
+    match s.to_ascii_lowercase().as_str() {
+        "square" => Ok(EyeShape::Square),
+        "rounded" => Ok(EyeShape::Rounded),
+        "circle" => Ok(EyeShape::Circle),
+        "leaf" => Ok(EyeShape::Leaf),
+        _ => Err(format!("neznámý tvar značky „{s}“ (square, rounded, circle, leaf)")),
+    }
+}
+
+fn parse_ec(s: &str) -> Result<EcLevel, String> {
+    match s.to_ascii_uppercase().as_str() {
+        "L" => Ok(EcLevel::L),
+        "M" => Ok(EcLevel::M),
+        "Q" => Ok(EcLevel::Q),
+        "H" => Ok(EcLevel::H),
+        _ => Err(format!("neznámá úroveň korekce „{s}“ (L, M, Q, H)")),
+    }
+}
+
+fn parse_rgb(s: &str) -> Result<(u8, u8, u8), String> {
+    kju_ar::parse_rgb(s).ok_or_else(|| format!("neplatná barva „{s}“ (očekávám #RRGGBB)"))
+}
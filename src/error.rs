@@ -0,0 +1,79 @@
+//! Chyby knihovny.
+
+use std::path::PathBuf;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Text nejde zakódovat (neplatný nebo příliš dlouhý pro zvolenou korekci).
+    #[error("Neplatné URL pro QR? (nebo je příliš dlouhé pro zvolenou korekci)")]
+    Encode(#[from] qrcode::types::QrError),
+
+    #[error("Nejde otevřít obrázek: {}", path.display())]
+    OpenImage { path: PathBuf, source: image::ImageError },
+
+    #[error("Nejde otevřít logo: {}", path.display())]
+    OpenLogo { path: PathBuf, source: image::ImageError },
+
+    #[error("Logo nejde zakódovat do PNG")]
+    EncodeLogo(#[source] image::ImageError),
+
+    /// Kódování nebo uložení rastru selhalo.
+    #[error("Uložení obrázku selhalo: {}", path.display())]
+    SaveImage { path: PathBuf, source: image::ImageError },
+
+    #[error("Zápis selhal: {}", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+
+    #[error("Nepodporovaná přípona: .{0} (použij .jpg/.jpeg/.png/.tif/.tiff/.pdf)")]
+    UnsupportedExtension(String),
+
+    #[error("Vlož aspoň jednu URL (po řádku).")]
+    NoInput,
+
+    /// Formulář strukturovaného obsahu (Wi-Fi, kontakt, platba…) není vyplněný správně.
+    #[error("Neplatný obsah QR: {0}")]
+    Payload(String),
+
+    #[error("Pro popisek nebylo nalezeno písmo (nastav KJU_AR_FONT na soubor .ttf)")]
+    NoCaptionFont,
+
+    #[error("Nejde číst tabulku: {}", path.display())]
+    ReadTable { path: PathBuf, source: csv::Error },
+
+    #[error("Nejde zapsat tabulku: {}", path.display())]
+    WriteTable { path: PathBuf, source: csv::Error },
+
+    /// Neplatná hodnota v tabulce (řádek číslovaný od 1 včetně záhlaví).
+    #[error("Řádek {row}: {message}")]
+    TableRow { row: usize, message: String },
+
+    /// Zpětné dekódování uloženého QR selhalo nebo vrátilo jiný obsah.
+    #[error("QR nejde přečíst: {0}")]
+    Unreadable(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    /// Zpráva včetně příčin („Uložení selhalo: …: důvod“) – pro reporty a stavový řádek.
+    pub fn describe(&self) -> String {
+        let mut msg = self.to_string();
+        let mut src = std::error::Error::source(self);
+        while let Some(e) = src {
+            msg.push_str(": ");
+            msg.push_str(&e.to_string());
+            src = e.source();
+        }
+        msg
+    }
+
+    pub(crate) fn io(path: impl Into<PathBuf>) -> impl FnOnce(std::io::Error) -> Self {
+        let path = path.into();
+        move |source| Error::Io { path, source }
+    }
+
+    pub(crate) fn save_image(path: impl Into<PathBuf>) -> impl FnOnce(image::ImageError) -> Self {
+        let path = path.into();
+        move |source| Error::SaveImage { path, source }
+    }
+}
@@ -0,0 +1,533 @@
+//! Ukládání QR kódů do souborů – jednotlivě i hromadně.
+
+use crate::caption::{add_caption, Caption};
+use crate::error::{Error, Result};
+use crate::pdf::{build_image_pdf, build_qr_pdf};
+use crate::render::{build_qr_image, build_qr_svg, flatten_rgba_to_rgb};
+use crate::report::{BulkOutcome, BulkProgress, BulkReport};
+use crate::style::{Logo, OutputFormat, QrStyle};
+use crate::utm::Utm;
+#[cfg(feature = "verify")]
+use crate::verify::verify_qr_file;
+use image::{DynamicImage, RgbImage, RgbaImage};
+use rayon::prelude::*;
+use sha1::{Digest, Sha1};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Uloží obecný RGBA obrázek podle přípony (png/jpg/tif/pdf) – pro overlay.
+/// `pdf_mm_per_px` určuje fyzickou velikost pixelu, pokud se ukládá do PDF;
+/// s `dpi` se do PNG/TIFF/JPEG zapíše rozlišení stejně jako u `save_qr`.
+pub fn save_image_rgba(img: &DynamicImage, outp: &Path, pdf_mm_per_px: f32, dpi: Option<u32>) -> Result<()> {
+    let ext = outp.extension().and_then(|e| e.to_str()).unwrap_or("").to_ascii_lowercase();
+    match (ext.as_str(), dpi) {
+        ("jpg" | "jpeg", _) => save_jpeg(&img.to_rgb8(), outp, dpi)?,
+        ("png", Some(dpi)) => save_with_dpi(&img.to_rgba8(), outp, OutputFormat::Png, dpi)?,
+        ("tif" | "tiff", Some(dpi)) => save_with_dpi(&img.to_rgba8(), outp, OutputFormat::Tiff, dpi)?,
+        ("png" | "tif" | "tiff" | "", _) => img.save(outp).map_err(Error::save_image(outp))?,
+        ("pdf", _) => {
+            let pdf = build_image_pdf(&img.to_rgba8(), pdf_mm_per_px);
+            fs::write(outp, pdf).map_err(Error::io(outp))?;
+        }
+        (other, _) => return Err(Error::UnsupportedExtension(other.to_string())),
+    }
+    Ok(())
+}
+
+/// Uloží samostatný QR ve zvoleném formátu.
+/// - PNG/TIFF: zachová alfu; se `style.dpi` zapíše i rozlišení (stejně JPEG).
+/// - JPEG: slije alfu na pozadí (bílá pokud `background_rgb=None`, jinak zadaná barva).
+/// - SVG: vektorově přímo z matice modulů (bez převzorkování).
+/// - PDF: vektorová stránka o straně `style.print_size_mm`.
+///
+/// Volitelný `caption` se vypíše pod QR (pruh v barvě pozadí), nebo QR obklopí rámeček QR Platby.
+pub fn save_qr(
+    url: &str,
+    style: &QrStyle,
+    logo: Option<&Logo>,
+    caption: Option<Caption>,
+    outp: &Path,
+    fmt: OutputFormat,
+) -> Result<()> {
+    match fmt {
+        OutputFormat::Png | OutputFormat::Tiff => {
+            let qr = build_captioned_image(url, style, logo, caption)?;
+            match style.dpi {
+                Some(dpi) => save_with_dpi(&qr, outp, fmt, dpi)?,
+                None => DynamicImage::ImageRgba8(qr).save(outp).map_err(Error::save_image(outp))?,
+            }
+        }
+        OutputFormat::Jpeg => {
+            let qr = build_captioned_image(url, style, logo, caption)?;
+            let bg = style.background_rgb.unwrap_or((255, 255, 255));
+            save_jpeg(&flatten_rgba_to_rgb(&qr, bg), outp, style.dpi)?;
+        }
+        OutputFormat::Svg => {
+            let svg = build_qr_svg(url, style, logo, caption)?;
+            fs::write(outp, svg).map_err(Error::io(outp))?;
+        }
+        OutputFormat::Pdf => {
+            let pdf = build_qr_pdf(url, style, logo, caption)?;
+            fs::write(outp, pdf).map_err(Error::io(outp))?;
+        }
+    }
+    Ok(())
+}
+
+fn build_captioned_image(
+    url: &str,
+    style: &QrStyle,
+    logo: Option<&Logo>,
+    caption: Option<Caption>,
+) -> Result<RgbaImage> {
+    let qr = build_qr_image(url, style, logo)?;
+    match caption {
+        Some(caption) => add_caption(&qr, caption, style),
+        None => Ok(qr),
+    }
+}
+
+fn save_jpeg(rgb: &RgbImage, outp: &Path, dpi: Option<u32>) -> Result<()> {
+    use image::codecs::jpeg::{JpegEncoder, PixelDensity};
+
+    let mut out = fs::File::create(outp).map_err(Error::io(outp))?;
+    let mut enc = JpegEncoder::new_with_quality(&mut out, 92);
+    if let Some(dpi) = dpi {
+        enc.set_pixel_density(PixelDensity::dpi(dpi.min(u16::MAX as u32) as u16));
+    }
+    enc.encode_image(rgb).map_err(Error::save_image(outp))
+}
+
+/// PNG (pHYs v pixelech na metr) nebo TIFF (XResolution/YResolution v palcích) s rozlišením,
+/// aby sazební programy QR umístily ve správné fyzické velikosti.
+fn save_with_dpi(img: &RgbaImage, outp: &Path, fmt: OutputFormat, dpi: u32) -> Result<()> {
+    let file = std::io::BufWriter::new(fs::File::create(outp).map_err(Error::io(outp))?);
+    let (w, h) = img.dimensions();
+    // chyby kodérů se hlásí stejně jako chyby `image`
+    let encode_err = |e: Box<dyn std::error::Error + Send + Sync>| {
+        Error::save_image(outp)(image::ImageError::IoError(std::io::Error::other(e)))
+    };
+    if fmt == OutputFormat::Tiff {
+        use tiff::encoder::{colortype::RGBA8, Rational, TiffEncoder};
+        use tiff::tags::ResolutionUnit;
+
+        let mut enc = TiffEncoder::new(file).map_err(|e| encode_err(e.into()))?;
+        let mut tif = enc.new_image::<RGBA8>(w, h).map_err(|e| encode_err(e.into()))?;
+        tif.resolution(ResolutionUnit::Inch, Rational { n: dpi, d: 1 });
+        return tif.write_data(img.as_raw()).map_err(|e| encode_err(e.into()));
+    }
+    let mut enc = png::Encoder::new(file, w, h);
+    enc.set_color(png::ColorType::Rgba);
+    enc.set_depth(png::BitDepth::Eight);
+    let ppm = (dpi as f32 / 0.0254).round() as u32;
+    enc.set_pixel_dims(Some(png::PixelDimensions { xppu: ppm, yppu: ppm, unit: png::Unit::Meter }));
+    let mut writer = enc.write_header().map_err(|e| encode_err(e.into()))?;
+    writer.write_image_data(img.as_raw()).map_err(|e| encode_err(e.into()))?;
+    writer.finish().map_err(|e| encode_err(e.into()))
+}
+
+/// Jedna položka hromadného exportu; nepovinná pole přepisují společný styl.
+#[derive(Clone, Debug, Default)]
+pub struct BulkItem {
+    pub payload: String,
+    pub file_name: Option<String>, // None => `make_qr_filename`
+    pub module_rgb: Option<(u8, u8, u8)>,
+    pub background_rgb: Option<(u8, u8, u8)>,
+    pub size_px: Option<u32>,
+    pub caption: Option<String>,
+    pub utm: Utm, // prázdná pole => `BulkOptions::utm`
+}
+
+impl BulkItem {
+    pub fn new(payload: impl Into<String>) -> Self {
+        Self { payload: payload.into(), ..Self::default() }
+    }
+
+    /// Společný styl s přepsanými hodnotami této položky. Při pevném DPI se vlastní
+    /// velikost v px převede na tiskový rozměr (`size_px` by se jinak neuplatnila).
+    pub fn style(&self, base: &QrStyle) -> QrStyle {
+        let print_size_mm = match (self.size_px, base.dpi) {
+            (Some(px), Some(dpi)) => px as f32 / dpi.max(1) as f32 * 25.4,
+            _ => base.print_size_mm,
+        };
+        QrStyle {
+            size_px: self.size_px.unwrap_or(base.size_px),
+            print_size_mm,
+            module_rgb: self.module_rgb.unwrap_or(base.module_rgb),
+            // vlastní barva položky nahradí i společný přechod
+            gradient: if self.module_rgb.is_some() { None } else { base.gradient.clone() },
+            background_rgb: self.background_rgb.or(base.background_rgb),
+            ..base.clone()
+        }
+    }
+
+    /// Obsah QR – odkaz doplněný o UTM parametry (vlastní, jinak společné).
+    pub fn tracked_payload(&self, base: &Utm) -> String {
+        self.utm.or(base).apply(&self.payload)
+    }
+}
+
+/// Volby hromadného exportu.
+#[derive(Clone, Debug, Default)]
+pub struct BulkOptions {
+    pub workers: usize, // vlákna pro vykreslování (0 = počet jader)
+    #[cfg(feature = "verify")]
+    pub verify: bool,   // každý uložený QR zpětně dekódovat (`verify_qr_file`)
+    pub utm: Utm,       // parametry kampaně pro všechny odkazy
+}
+
+/// Hromadně uloží samostatné QR do složky `dir` (na všech jádrech).
+/// Chyba položky export nepřeruší – zapíše se do reportu a nedokončený soubor se smaže.
+/// `Err` jen pokud není co exportovat nebo nejde vytvořit složka.
+pub fn export_bulk(
+    items: &[BulkItem],
+    dir: &Path,
+    style: &QrStyle,
+    logo: Option<&Logo>,
+    fmt: OutputFormat,
+) -> Result<BulkReport> {
+    export_bulk_with_progress(items, dir, style, logo, fmt, BulkOptions::default(), |_| true)
+}
+
+/// Jako [`export_bulk`], ale s volbami (počet vláken, ověření čitelnosti) a hlášením průběhu.
+/// `on_progress` se volá před každou položkou (z pracovních vláken); vrátí-li `false`,
+/// další položky se už nezačnou (uložené soubory zůstanou) a report má `cancelled`.
+///
+/// Názvy souborů se přidělí předem (z odkazu bez UTM), takže nezávisí na pořadí dokončení;
+/// `outcomes` jsou vždy seřazené podle vstupu a nesou zakódovaný obsah včetně UTM.
+pub fn export_bulk_with_progress(
+    items: &[BulkItem],
+    dir: &Path,
+    style: &QrStyle,
+    logo: Option<&Logo>,
+    fmt: OutputFormat,
+    options: BulkOptions,
+    on_progress: impl Fn(&BulkProgress) -> bool + Sync,
+) -> Result<BulkReport> {
+    if items.is_empty() {
+        return Err(Error::NoInput);
+    }
+
+    fs::create_dir_all(dir).map_err(Error::io(dir))?;
+
+    let mut used = HashSet::new();
+    let paths: Vec<PathBuf> =
+        items.iter().enumerate().map(|(i, item)| dir.join(bulk_file_name(i + 1, item, fmt, &mut used))).collect();
+
+    let done = AtomicUsize::new(0);
+    let cancelled = AtomicBool::new(false);
+    let export_one = |i: usize| -> Option<BulkOutcome> {
+        let item = &items[i];
+        let payload = item.tracked_payload(&options.utm);
+        let progress = BulkProgress { done: done.load(Ordering::Relaxed), total: items.len(), payload: payload.clone() };
+        if cancelled.load(Ordering::Relaxed) || !on_progress(&progress) {
+            cancelled.store(true, Ordering::Relaxed);
+            return None;
+        }
+        let path = paths[i].clone();
+        let item_style = item.style(style);
+        let res = save_qr(&payload, &item_style, logo, item.caption.as_deref().map(Caption::Text), &path, fmt);
+        if res.is_err() {
+            let _ = fs::remove_file(&path);
+        }
+        #[cfg(feature = "verify")]
+        let unreadable = (options.verify && res.is_ok())
+            .then(|| verify_qr_file(&payload, &path, fmt).err().map(|e| e.describe()))
+            .flatten();
+        #[cfg(not(feature = "verify"))]
+        let unreadable = None;
+        done.fetch_add(1, Ordering::Relaxed);
+        Some(BulkOutcome {
+            index1: i + 1,
+            payload,
+            path,
+            error: res.err().map(|e| e.describe()),
+            unreadable,
+        })
+    };
+
+    // `with_max_len(1)`: vlákna si berou položky po jedné, takže zrušení platí hned
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(options.workers).build().ok();
+    let run = || -> Vec<Option<BulkOutcome>> { (0..items.len()).into_par_iter().with_max_len(1).map(export_one).collect() };
+    let outcomes = match &pool {
+        Some(pool) => pool.install(run),
+        None => run(), // vlákna nejde vytvořit → globální pool
+    };
+
+    Ok(BulkReport {
+        dir: dir.to_path_buf(),
+        outcomes: outcomes.into_iter().flatten().collect(),
+        total: items.len(),
+        cancelled: cancelled.into_inner(),
+    })
+}
+
+/// Název souboru položky – zadaný (očištěný, s příponou formátu) nebo automatický.
+/// Kolize v rámci jednoho exportu dostanou příponu `_<pořadí>` (případně `_<pořadí>_2`…).
+fn bulk_file_name(index1: usize, item: &BulkItem, fmt: OutputFormat, used: &mut HashSet<String>) -> String {
+    let stem = item
+        .file_name
+        .as_deref()
+        .map(|n| {
+            let n = n.trim();
+            let p = Path::new(n);
+            let n = match p.extension().and_then(|e| e.to_str()) {
+                Some(e) if OutputFormat::from_ext(e).is_some() => p.file_stem().and_then(|s| s.to_str()).unwrap_or(n),
+                _ => n,
+            };
+            sanitize_user_filename(n)
+        })
+        .filter(|s| !s.is_empty());
+    let name = match stem {
+        Some(stem) => format!("{stem}.{}", fmt.ext()),
+        None => make_qr_filename(index1, &item.payload, fmt),
+    };
+    if used.insert(name.to_lowercase()) {
+        return name;
+    }
+    // náhradní název může sám kolidovat s dříve zadaným (`a_3`, `a`, `a`) → `_<pořadí>_2`…
+    let (stem, ext) = name.rsplit_once('.').unwrap_or((&name, fmt.ext()));
+    (1..)
+        .map(|n| if n == 1 { format!("{stem}_{index1}.{ext}") } else { format!("{stem}_{index1}_{n}.{ext}") })
+        .find(|name| used.insert(name.to_lowercase()))
+        .expect("nekonečná řada názvů")
+}
+
+/// Rozparsuje URL po řádcích (prázdné řádky vynechá).
+pub fn parse_url_lines(s: &str) -> Vec<String> {
+    s.lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty())
+        .map(|l| l.to_string())
+        .collect()
+}
+
+pub fn default_out_path(in_path: Option<&Path>) -> PathBuf {
+    match in_path {
+        Some(p) => {
+            let parent = p.parent().unwrap_or_else(|| Path::new("."));
+            let stem = p.file_stem().unwrap_or_default().to_string_lossy();
+            let ext = p.extension().and_then(|e| e.to_str()).unwrap_or("png");
+            parent.join(format!("out_{}.{}", stem, ext))
+        }
+        None => default_qr_out_path(),
+    }
+}
+
+pub fn default_qr_out_path() -> PathBuf {
+    PathBuf::from("qr.png")
+}
+
+pub fn default_bulk_dir() -> PathBuf {
+    PathBuf::from("qr_export")
+}
+
+/// Název souboru pro hromadný export: pořadí, slug z URL a zkrácený SHA-1.
+pub fn make_qr_filename(index1: usize, url: &str, fmt: OutputFormat) -> String {
+    let slug = make_slug_from_url(url);
+    let hash10 = sha1_hex10(url);
+    let base = if slug.is_empty() {
+        format!("qr_{:03}_{}", index1, hash10)
+    } else {
+        format!("qr_{:03}_{}_{}", index1, slug, hash10)
+    };
+    format!("{base}.{}", fmt.ext())
+}
+
+fn sha1_hex10(s: &str) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(s.as_bytes());
+    let bytes = hasher.finalize();
+    let mut out = String::with_capacity(10);
+    for b in bytes.iter().take(5) {
+        out.push_str(&format!("{:02x}", b));
+    }
+    out
+}
+
+fn make_slug_from_url(url: &str) -> String {
+    // jednoduchý slug: host + poslední segment cesty
+    let u = url.trim().trim_end_matches('/');
+    let host = u.split("://").nth(1).unwrap_or(u);
+    let host = host.split('/').next().unwrap_or("");
+    let last = u.rsplit('/').next().unwrap_or("");
+    let mut s = String::new();
+    if !host.is_empty() {
+        s.push_str(&sanitize_for_filename(host));
+    }
+    if !last.is_empty() && last != host {
+        if !s.is_empty() {
+            s.push('_');
+        }
+        s.push_str(&sanitize_for_filename(last));
+    }
+    if s.len() > 40 {
+        s.truncate(40);
+    }
+    s.trim_matches('_').to_string()
+}
+
+/// Název zadaný uživatelem – zachová diakritiku, odstraní jen znaky neplatné v cestě.
+fn sanitize_user_filename(s: &str) -> String {
+    let out: String = s
+        .chars()
+        .map(|c| if c.is_control() || r#"/\:*?"<>|"#.contains(c) { '_' } else { c })
+        .collect();
+    out.trim_matches(|c: char| c == '.' || c.is_whitespace()).to_string()
+}
+
+fn sanitize_for_filename(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for ch in s.chars() {
+        if ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' {
+            out.push(ch);
+        } else if ch.is_ascii() {
+            out.push('-');
+        } // ne-ASCII: vynecháme
+    }
+    // sloučit víc '-' do jednoho
+    let mut compact = String::with_capacity(out.len());
+    let mut prev_dash = false;
+    for c in out.chars() {
+        if c == '-' {
+            if !prev_dash {
+                compact.push(c);
+            }
+            prev_dash = true;
+        } else {
+            compact.push(c);
+            prev_dash = false;
+        }
+    }
+    compact.trim_matches('-').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn row_size_maps_to_print_size_with_dpi() {
+        let base = QrStyle { dpi: Some(300), print_size_mm: 50.0, ..QrStyle::default() };
+        let item = BulkItem { size_px: Some(600), ..BulkItem::new("https://example.com") };
+        let style = item.style(&base);
+        assert!((style.print_size_mm - 50.8).abs() < 1e-3);
+        assert_eq!(style.raster_px(42), 600); // 42 + 2·4 modulů = 50 → 12 px na modul
+        // bez vlastní velikosti zůstane společný rozměr
+        assert_eq!(BulkItem::new("x").style(&base).print_size_mm, 50.0);
+    }
+
+    fn files_in(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> =
+            fs::read_dir(dir).unwrap().map(|e| e.unwrap().file_name().to_string_lossy().into_owned()).collect();
+        names.sort();
+        names
+    }
+
+    fn named(payload: &str, file_name: &str) -> BulkItem {
+        BulkItem { file_name: Some(file_name.into()), ..BulkItem::new(payload) }
+    }
+
+    #[test]
+    fn bulk_names_are_unique_and_stable() {
+        let items = [
+            named("https://example.com/a", "report"),
+            BulkItem::new("https://example.com/a"), // stejný obsah, automatický název
+            named("https://example.com/b", " Report.PNG "),
+            named("https://example.com/c", "report.svg"), // přípona jiného formátu se zahodí
+            named("https://example.com/d", "report_4"),
+            named("https://example.com/e", "a/b:c"),
+            BulkItem::new("https://example.com/a"),
+        ];
+        let mut used = HashSet::new();
+        let names: Vec<String> =
+            items.iter().enumerate().map(|(i, item)| bulk_file_name(i + 1, item, OutputFormat::Png, &mut used)).collect();
+        assert_eq!(
+            names,
+            [
+                "report.png".to_string(),
+                make_qr_filename(2, "https://example.com/a", OutputFormat::Png),
+                "Report_3.png".into(),
+                "report_4.png".into(),
+                "report_4_5.png".into(),
+                "a_b_c.png".into(),
+                make_qr_filename(7, "https://example.com/a", OutputFormat::Png),
+            ]
+        );
+        assert_ne!(names[1], names[6]);
+
+        // náhradní název `a_3` už je obsazený zadaným
+        let items = [
+            named("https://example.com/x", "a_3"),
+            named("https://example.com/y", "a"),
+            named("https://example.com/z", "a"),
+        ];
+        let mut used = HashSet::new();
+        let names: Vec<String> =
+            items.iter().enumerate().map(|(i, item)| bulk_file_name(i + 1, item, OutputFormat::Png, &mut used)).collect();
+        assert_eq!(names, ["a_3.png", "a.png", "a_3_2.png"]);
+    }
+
+    #[test]
+    fn bulk_export_is_deterministic_across_workers() {
+        let mut items: Vec<BulkItem> = (0..12)
+            .map(|i| match i % 4 {
+                0 => named("https://example.com/dup", "dup"),
+                1 => BulkItem::new("https://example.com/dup"),
+                2 => named(&format!("https://example.com/{i}"), "DUP.svg"),
+                _ => BulkItem::new(format!("https://example.com/{i}")),
+            })
+            .collect();
+        items[5].payload = "x".repeat(8000); // nevejde se do QR → chyba položky
+        let style = QrStyle { size_px: 64, ..QrStyle::default() };
+
+        let mut runs = Vec::new();
+        for workers in [1, 3, 8] {
+            let tmp = tempfile::tempdir().unwrap();
+            let dir = tmp.path().join("qr"); // složku vytvoří až export
+            let options = BulkOptions { workers, ..BulkOptions::default() };
+            let report = export_bulk_with_progress(&items, &dir, &style, None, OutputFormat::Png, options, |_| true).unwrap();
+            assert!(!report.cancelled);
+            assert_eq!(report.outcomes.iter().map(|o| o.index1).collect::<Vec<_>>(), (1..=12).collect::<Vec<_>>());
+            assert_eq!(report.failed_count(), 1);
+            assert!(report.outcomes[5].error.is_some());
+            // na disku jsou právě uložené položky, chybná nezanechá soubor
+            let mut saved: Vec<String> = report
+                .outcomes
+                .iter()
+                .filter(|o| o.error.is_none())
+                .map(|o| o.path.file_name().unwrap().to_string_lossy().into_owned())
+                .collect();
+            saved.sort();
+            assert_eq!(files_in(&dir), saved);
+            assert!(!report.outcomes[5].path.exists());
+            let names: Vec<_> = report.outcomes.iter().map(|o| o.path.file_name().unwrap().to_owned()).collect();
+            runs.push(names);
+        }
+        assert!(runs.windows(2).all(|w| w[0] == w[1]), "{runs:?}");
+        let unique: HashSet<_> = runs[0].iter().map(|n| n.to_string_lossy().to_lowercase()).collect();
+        assert_eq!(unique.len(), items.len());
+    }
+
+    #[test]
+    fn cancelled_bulk_export_stops_cleanly() {
+        let items: Vec<BulkItem> = (0..10).map(|i| BulkItem::new(format!("https://example.com/{i}"))).collect();
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("qr");
+        let started = AtomicUsize::new(0);
+        let options = BulkOptions { workers: 1, ..BulkOptions::default() };
+        let style = QrStyle { size_px: 64, ..QrStyle::default() };
+        let report = export_bulk_with_progress(&items, &dir, &style, None, OutputFormat::Png, options, |_| {
+            started.fetch_add(1, Ordering::Relaxed) < 3
+        })
+        .unwrap();
+        assert!(report.cancelled);
+        assert_eq!(report.total, 10);
+        assert_eq!(report.outcomes.iter().map(|o| o.index1).collect::<Vec<_>>(), [1, 2, 3]);
+        let saved: Vec<String> =
+            report.outcomes.iter().map(|o| o.path.file_name().unwrap().to_string_lossy().into_owned()).collect();
+        assert_eq!(files_in(&dir), saved);
+    }
+}
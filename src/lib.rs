@@ -0,0 +1,50 @@
+//! Kjů ár – generování QR kódů (rastr, SVG, PDF) a jejich vkládání do obrázků.
+//!
+//! ```no_run
+//! use kju_ar::{save_qr, OutputFormat, QrStyle};
+//!
+//! let style = QrStyle { size_px: 512, background_rgb: Some((255, 255, 255)), ..QrStyle::default() };
+//! save_qr("https://example.com", &style, None, None, "qr.svg".as_ref(), OutputFormat::Svg)?;
+//! # Ok::<(), kju_ar::Error>(())
+//! ```
+
+mod caption;
+mod error;
+mod export;
+mod overlay;
+mod payload;
+mod pdf;
+mod render;
+mod report;
+mod shape;
+mod style;
+mod table;
+mod utm;
+#[cfg(feature = "verify")]
+mod verify;
+
+pub use caption::{add_caption, Caption};
+pub use error::{Error, Result};
+pub use export::{
+    default_bulk_dir, default_out_path, default_qr_out_path, export_bulk, export_bulk_with_progress, make_qr_filename,
+    parse_url_lines, save_image_rgba, save_qr, BulkItem, BulkOptions,
+};
+pub use overlay::{overlay_into_image, overlay_margin_px, Corner, OverlaySpec};
+pub use payload::{
+    ContactFormat, ContactPayload, EmailPayload, EpcPayload, EventPayload, GeoPayload, PaymentPayload, PhonePayload,
+    SmsFormat, SmsPayload, WifiPayload, WifiSecurity,
+};
+pub use pdf::{build_image_pdf, build_qr_pdf};
+pub use qrcode::EcLevel;
+pub use render::{build_qr_image, build_qr_svg, flatten_rgba_to_rgb, qr_version, raster_size_px};
+pub use report::{BulkOutcome, BulkProgress, BulkReport};
+#[cfg(feature = "serde")]
+pub use style::ec_level_serde;
+pub use style::{
+    load_logo, min_ec_for_logo, parse_rgb, EyeShape, EyeStyle, Gradient, GradientKind, GradientStop, Logo, ModuleShape,
+    OutputFormat, QrStyle,
+};
+pub use table::{ColumnMapping, Table};
+pub use utm::Utm;
+#[cfg(feature = "verify")]
+pub use verify::{verify_overlay, verify_qr_file};
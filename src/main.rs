@@ -1,1170 +1,1921 @@
-#![cfg_attr(all(target_os = "windows", not(debug_assertions)), windows_subsystem = "windows")]
-
-use eframe::egui;
-use egui::{Align, Color32, ColorImage, ComboBox, Layout, TextEdit, TextureHandle, TextureOptions, Vec2};
-use image::{imageops, DynamicImage, Rgb, RgbImage, Rgba, RgbaImage};
-use imageproc::drawing::{draw_filled_circle_mut, draw_filled_rect_mut};
-use imageproc::rect::Rect;
-use qrcode::{Color as QrColor, QrCode};
-use rfd::FileDialog;
-use sha1::{Digest, Sha1};
-use std::fs;
-use std::path::{Path, PathBuf};
-use std::sync::mpsc::{channel, Receiver};
-use std::time::SystemTime;
-
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
-enum Corner {
-    Southeast,
-    Southwest,
-    Northeast,
-    Northwest,
-    Custom, // X/Y od levého-horního
-}
-
-enum JobResult {
-    Ok(PathBuf),
-    Err(String),
-}
-
-#[derive(Clone, Copy)]
-enum SaveMode {
-    OverlayIntoImage,
-    QrOnlySingle,
-    QrOnlyBulk,
-}
-
-#[derive(Clone, Copy, PartialEq, Eq)]
-enum OutputFormat {
-    Png,
-    Jpeg,
-    Tiff,
-}
-impl OutputFormat {
-    fn ext(self) -> &'static str {
-        match self {
-            OutputFormat::Png => "png",
-            OutputFormat::Jpeg => "jpg",
-            OutputFormat::Tiff => "tif",
-        }
-    }
-}
-
-struct AppState {
-    // Režimy
-    bulk_mode: bool,
-
-    // URL vstup
-    url: String,          // single
-    bulk_urls: String,    // multi – po řádcích
-
-    // Volby výstupu
-    output_path: Option<PathBuf>,   // single QR i overlay
-    export_dir: Option<PathBuf>,    // složka pro hromadné
-    out_format: OutputFormat,
-
-    // Vstupní obrázek (jen overlay)
-    input_path: Option<PathBuf>,
-    base_dims: Option<(u32, u32)>,
-
-    // QR parametry
-    qr_size_px: u32,
-    corner: Corner,
-    offset_x: i32,
-    offset_y: i32,
-
-    // Vzhled QR
-    rounding_percent: u8,       // 0–50 % z velikosti modulu
-    module_color: Color32,      // barva „tmavých“ modulů
-    background_color: Color32,  // barva pozadí (použije se, když není „Odstranit pozadí“)
-    qr_alpha_percent: u8,       // 0–100 %
-    cut_white_background: bool, // true => pozadí QR bude plně průhledné
-
-    // Výsledky / status
-    last_message: String,
-    last_saved_path: Option<PathBuf>,
-
-    // Náhled
-    preview: Option<TextureHandle>,
-    preview_key: String,
-    preview_error: Option<String>,
-
-    // Asynchronní uložení
-    is_busy: bool,
-    job_rx: Option<Receiver<JobResult>>,
-
-    // Modální okno s výsledkem
-    result_modal_open: bool,
-}
-
-impl Default for AppState {
-    fn default() -> Self {
-        Self {
-            bulk_mode: false,
-
-            url: "".to_owned(),
-            bulk_urls: "".to_owned(),
-
-            output_path: None,
-            export_dir: None,
-            out_format: OutputFormat::Png,
-
-            input_path: None,
-            base_dims: None,
-
-            qr_size_px: 160,
-            corner: Corner::Southeast,
-            offset_x: 10,
-            offset_y: 10,
-
-            rounding_percent: 0,
-            module_color: Color32::BLACK,
-            background_color: Color32::WHITE,
-            qr_alpha_percent: 85,
-            cut_white_background: true,
-
-            last_message: String::new(),
-            last_saved_path: None,
-
-            preview: None,
-            preview_key: String::new(),
-            preview_error: None,
-
-            is_busy: false,
-            job_rx: None,
-
-            result_modal_open: false,
-        }
-    }
-}
-
-impl eframe::App for AppState {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        // Poll výsledků background jobu
-        if let Some(rx) = &self.job_rx {
-            if let Ok(msg) = rx.try_recv() {
-                self.is_busy = false;
-                self.job_rx = None;
-                match msg {
-                    JobResult::Ok(path) => {
-                        self.last_saved_path = Some(path.clone());
-                        self.last_message = format!("Uloženo: {}", path.display());
-                    }
-                    JobResult::Err(e) => {
-                        self.last_saved_path = None;
-                        self.last_message = format!("Chyba: {e}");
-                    }
-                }
-                self.result_modal_open = true;
-            }
-        }
-
-        egui::TopBottomPanel::top("top").show(ctx, |ui| {
-            ui.with_layout(Layout::left_to_right(Align::Center), |ui| {
-                ui.heading("Kjů ár");
-                ui.add_space(12.0);
-                ui.label("Vlož QR do obrázku nebo hromadně ulož samostatné QR.");
-            });
-        });
-
-        egui::CentralPanel::default().show(ctx, |ui| {
-            ui.spacing_mut().item_spacing = egui::vec2(10.0, 10.0);
-
-            ui.columns(2, |cols| {
-                // === LEVÝ SLOUPEC – ovládání ===
-                cols[0].vertical(|ui| {
-                    ui.add_enabled_ui(!self.is_busy && !self.result_modal_open, |ui| {
-                        ui.group(|ui| {
-                            ui.horizontal(|ui| {
-                                ui.label("Režim:");
-                                ui.selectable_value(&mut self.bulk_mode, false, "Jednotlivě");
-                                ui.selectable_value(&mut self.bulk_mode, true, "Hromadně (URL po řádcích)");
-                            });
-                        });
-
-                        ui.group(|ui| {
-                            if self.bulk_mode {
-                                ui.label("Vlož víc URL – každé na samostatný řádek:");
-                                if ui
-                                    .add(
-                                        egui::TextEdit::multiline(&mut self.bulk_urls)
-                                            .hint_text("https://...\nhttps://...\n...")
-                                            .desired_rows(6)
-                                            .desired_width(f32::INFINITY),
-                                    )
-                                    .changed()
-                                {
-                                    self.bump_preview();
-                                }
-                            } else {
-                                ui.label("Odkaz (URL) pro QR kód:");
-                                if ui
-                                    .add(
-                                        TextEdit::singleline(&mut self.url)
-                                            .hint_text("https://...")
-                                            .clip_text(true)
-                                            .desired_width(f32::INFINITY),
-                                    )
-                                    .changed()
-                                {
-                                    self.bump_preview();
-                                }
-                            }
-                        });
-
-                        // Soubory / výstup
-                        ui.group(|ui| {
-                            ui.label("Výstup:");
-                            if self.bulk_mode {
-                                if ui.button("Zvolit výstupní složku…").clicked() {
-                                    if let Some(dir) = FileDialog::new().pick_folder() {
-                                        self.export_dir = Some(dir);
-                                    }
-                                }
-                                ui.monospace(format!(
-                                    "Složka: {}",
-                                    self.export_dir
-                                        .as_deref()
-                                        .map(shorten)
-                                        .unwrap_or_else(|| format!("<automaticky: {}>", default_bulk_dir().display()))
-                                ));
-                                ui.horizontal(|ui| {
-                                    ui.label("Formát:");
-                                    ComboBox::from_id_source("fmt")
-                                        .selected_text(match self.out_format {
-                                            OutputFormat::Png => "PNG (.png)",
-                                            OutputFormat::Jpeg => "JPEG (.jpg)",
-                                            OutputFormat::Tiff => "TIFF (.tif)",
-                                        })
-                                        .show_ui(ui, |ui| {
-                                            ui.selectable_value(&mut self.out_format, OutputFormat::Png, "PNG (.png)");
-                                            ui.selectable_value(&mut self.out_format, OutputFormat::Jpeg, "JPEG (.jpg)");
-                                            ui.selectable_value(&mut self.out_format, OutputFormat::Tiff, "TIFF (.tif)");
-                                        });
-                                });
-                            } else {
-                                if ui.button("Zvolit výstupní soubor…").clicked() {
-                                    // návrh názvu: podle vstupu, jinak qr.png
-                                    let suggested = if self.input_path.is_some() {
-                                        default_out_path(self.input_path.as_ref())
-                                    } else {
-                                        default_qr_out_path()
-                                    };
-                                    if let Some(p) = FileDialog::new()
-                                        .set_file_name(
-                                            suggested
-                                                .file_name()
-                                                .unwrap_or_default()
-                                                .to_string_lossy(),
-                                        )
-                                        .save_file()
-                                    {
-                                        self.output_path = Some(p);
-                                    }
-                                }
-                                ui.monospace(format!(
-                                    "Soubor: {}",
-                                    self.output_path
-                                        .as_deref()
-                                        .map(shorten)
-                                        .unwrap_or_else(|| {
-                                            if self.input_path.is_some() {
-                                                "<automaticky: out_<původní>.jpg/png/tif>".to_string()
-                                            } else {
-                                                "<automaticky: qr.png>".to_string()
-                                            }
-                                        })
-                                ));
-                            }
-                        });
-
-                        // Vstupní obrázek (jen mimo hromadný režim)
-                        ui.add_enabled_ui(!self.bulk_mode, |ui| {
-                            ui.group(|ui| {
-                                ui.label("Zdrojový obrázek (pro vložení QR):");
-                                if ui.button("Vybrat zdrojový obrázek…").clicked() {
-                                    if let Some(p) = FileDialog::new()
-                                        .add_filter("Obrázky", &["jpg", "jpeg", "png", "tif", "tiff"])
-                                        .pick_file()
-                                    {
-                                        self.input_path = Some(p);
-                                        self.refresh_base_dims();
-                                        self.bump_preview();
-                                    }
-                                }
-                                ui.monospace(format!(
-                                    "Zdroj: {}",
-                                    self.input_path
-                                        .as_deref()
-                                        .map(shorten)
-                                        .unwrap_or_else(|| "<není vybráno>".to_string())
-                                ));
-                            });
-                        });
-
-                        ui.group(|ui| {
-                            ui.label("QR kód:");
-
-                            // Velikost
-                            if ui
-                                .add(
-                                    egui::Slider::new(&mut self.qr_size_px, 64..=2048)
-                                        .text("Velikost")
-                                        .suffix(" px")
-                                        .step_by(1.0),
-                                )
-                                .changed()
-                            {
-                                self.bump_preview();
-                            }
-
-                            // Zaoblení rohů (0–50 % modulu)
-                            if ui
-                                .add(
-                                    egui::Slider::new(&mut self.rounding_percent, 0..=50)
-                                        .text("Zaoblení rohů")
-                                        .suffix(" % modulu")
-                                        .step_by(1.0),
-                                )
-                                .changed()
-                            {
-                                self.bump_preview();
-                            }
-
-                            // Barva modulů
-                            ui.horizontal(|ui| {
-                                ui.label("Barva modulů:");
-                                let mut c = self.module_color;
-                                if egui::color_picker::color_edit_button_srgba(
-                                    ui,
-                                    &mut c,
-                                    egui::color_picker::Alpha::Opaque,
-                                )
-                                .changed()
-                                {
-                                    self.module_color = c;
-                                    self.bump_preview();
-                                }
-                            });
-
-                            // Barva pozadí (použije se, když není „Odstranit pozadí“)
-                            ui.horizontal(|ui| {
-                                ui.label("Pozadí QR:");
-                                let mut bg = self.background_color;
-                                let mut changed = false;
-                                ui.add_enabled_ui(!self.cut_white_background, |ui| {
-                                    if egui::color_picker::color_edit_button_srgba(
-                                        ui,
-                                        &mut bg,
-                                        egui::color_picker::Alpha::Opaque,
-                                    )
-                                    .changed()
-                                    {
-                                        changed = true;
-                                    }
-                                });
-                                if changed {
-                                    self.background_color = bg;
-                                    self.bump_preview();
-                                }
-                                if self.cut_white_background {
-                                    ui.small(" (nepoužije se při zapnutém „Odstranit pozadí“)");
-                                }
-                            });
-
-                            // Průhlednost QR – invertované ovládání (→ vpravo = 0 %, vlevo = 100 %)
-                            {
-                                let mut inv_alpha = 100 - self.qr_alpha_percent;
-                                let resp = ui.add(
-                                    egui::Slider::new(&mut inv_alpha, 0..=100)
-                                        .text("Průhlednost QR")
-                                        .suffix(" %")
-                                        .step_by(1.0),
-                                );
-                                if resp.changed() {
-                                    self.qr_alpha_percent = 100 - inv_alpha;
-                                    self.bump_preview();
-                                }
-                            }
-
-                            // „Odstranit pozadí“ (pozadí QR)
-                            if ui
-                                .checkbox(&mut self.cut_white_background, "Odstranit pozadí (průhledné pozadí)")
-                                .changed()
-                            {
-                                self.bump_preview();
-                            }
-
-                            ui.separator();
-
-                            // Pozice jen pokud není bulk a máme overlay mód
-                            ui.add_enabled_ui(!self.bulk_mode, |ui| {
-                                ui.label("Pozice (jen pro vložení do obrázku):");
-                                ComboBox::from_id_source("corner")
-                                    .selected_text(match self.corner {
-                                        Corner::Southeast => "pravý-dolní (SE)",
-                                        Corner::Southwest => "levý-dolní (SW)",
-                                        Corner::Northeast => "pravý-horní (NE)",
-                                        Corner::Northwest => "levý-horní (NW)",
-                                        Corner::Custom => "vlastní (X/Y)",
-                                    })
-                                    .show_ui(ui, |ui| {
-                                        let current = self.corner;
-                                        if ui.selectable_label(current == Corner::Southeast, "pravý-dolní (SE)").clicked() { self.corner = Corner::Southeast; self.bump_preview(); }
-                                        if ui.selectable_label(current == Corner::Southwest, "levý-dolní (SW)").clicked() { self.corner = Corner::Southwest; self.bump_preview(); }
-                                        if ui.selectable_label(current == Corner::Northeast, "pravý-horní (NE)").clicked() { self.corner = Corner::Northeast; self.bump_preview(); }
-                                        if ui.selectable_label(current == Corner::Northwest, "levý-horní (NW)").clicked() { self.corner = Corner::Northwest; self.bump_preview(); }
-                                        if ui.selectable_label(current == Corner::Custom, "vlastní (X/Y)").clicked() { self.corner = Corner::Custom; self.bump_preview(); }
-                                    });
-
-                                // Odsazení
-                                let (max_w, max_h) = self.base_dims.unwrap_or((4000, 4000));
-                                let slider_max_dx = max_w as i32;
-                                let slider_max_dy = max_h as i32;
-
-                                match self.corner {
-                                    Corner::Custom => {
-                                        ui.label("Souřadnice (px) od levého-horního rohu:");
-                                        if ui
-                                            .add(
-                                                egui::Slider::new(&mut self.offset_x, 0..=slider_max_dx)
-                                                    .text("X")
-                                                    .suffix(" px")
-                                                    .step_by(1.0),
-                                            )
-                                            .changed()
-                                        {
-                                            self.bump_preview();
-                                        }
-                                        if ui
-                                            .add(
-                                                egui::Slider::new(&mut self.offset_y, 0..=slider_max_dy)
-                                                    .text("Y")
-                                                    .suffix(" px")
-                                                    .step_by(1.0),
-                                            )
-                                            .changed()
-                                        {
-                                            self.bump_preview();
-                                        }
-                                    }
-                                    _ => {
-                                        ui.label("Odsazení od kraje (px):");
-                                        if ui
-                                            .add(
-                                                egui::Slider::new(&mut self.offset_x, 0..=slider_max_dx)
-                                                    .text("dx")
-                                                    .suffix(" px")
-                                                    .step_by(1.0),
-                                            )
-                                            .changed()
-                                        {
-                                            self.bump_preview();
-                                        }
-                                        if ui
-                                            .add(
-                                                egui::Slider::new(&mut self.offset_y, 0..=slider_max_dy)
-                                                    .text("dy")
-                                                    .suffix(" px")
-                                                    .step_by(1.0),
-                                            )
-                                            .changed()
-                                        {
-                                            self.bump_preview();
-                                        }
-                                    }
-                                }
-                            });
-                        });
-
-                        // Akce
-                        ui.horizontal(|ui| {
-                            let green = egui::Color32::from_rgb(16, 163, 74);
-
-                            if !self.bulk_mode {
-                                // Uložit do obrázku
-                                let overlay_btn = egui::Button::new(
-                                    egui::RichText::new("Vložit QR a uložit").color(egui::Color32::WHITE)
-                                )
-                                .fill(green);
-                                let overlay_enabled = self.input_path.is_some();
-                                if ui.add_enabled(overlay_enabled, overlay_btn).clicked() {
-                                    self.start_job(SaveMode::OverlayIntoImage);
-                                }
-
-                                // Uložit jen QR (single)
-                                let qr_btn = egui::Button::new(
-                                    egui::RichText::new("Uložit jen QR").color(egui::Color32::WHITE)
-                                )
-                                .fill(egui::Color32::from_rgb(52, 120, 246));
-                                if ui.add(qr_btn).clicked() {
-                                    self.start_job(SaveMode::QrOnlySingle);
-                                }
-                            } else {
-                                // Hromadné generování QR
-                                let bulk_btn = egui::Button::new(
-                                    egui::RichText::new("Vygenerovat QR (hromadně)").color(egui::Color32::WHITE)
-                                )
-                                .fill(egui::Color32::from_rgb(52, 120, 246));
-                                if ui.add(bulk_btn).clicked() {
-                                    self.start_job(SaveMode::QrOnlyBulk);
-                                }
-                            }
-
-                            if ui.button("Konec").clicked() {
-                                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
-                            }
-                        });
-
-                        if !self.last_message.is_empty() {
-                            ui.separator();
-                            ui.label(&self.last_message);
-                        }
-                    });
-
-                    if self.is_busy {
-                        ui.separator();
-                        ui.horizontal(|ui| {
-                            ui.add(egui::Spinner::new());
-                            ui.strong("Zpracovávám…");
-                        });
-                    }
-                });
-
-                // === PRAVÝ SLOUPEC – náhled ===
-                cols[1].vertical(|ui| {
-                    ui.group(|ui| {
-                        ui.label(if self.bulk_mode { "Živý náhled (první URL):" } else { "Živý náhled:" });
-                        self.ensure_preview(ctx);
-                        if let Some(err) = &self.preview_error {
-                            ui.colored_label(egui::Color32::RED, err);
-                        }
-                        if let Some(tex) = &self.preview {
-                            let max = Vec2::new(520.0, 520.0);
-                            let size = tex.size_vec2();
-                            let scale = (max.x / size.x).min(max.y / size.y).min(1.0);
-                            let desired = size * scale;
-                            ui.image((tex.id(), desired));
-                        } else {
-                            ui.monospace("— žádný náhled —");
-                        }
-                    });
-                });
-            });
-
-            // === Modální okno s výsledkem ===
-            if self.result_modal_open {
-                let mut is_open = true;
-                let mut close_now = false;
-
-                egui::Window::new(if self.last_saved_path.is_some() { "Hotovo" } else { "Chyba" })
-                    .collapsible(false)
-                    .resizable(false)
-                    .default_size([460.0, 160.0])
-                    .min_size([360.0, 120.0])
-                    .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
-                    .open(&mut is_open)
-                    .show(ctx, |ui| {
-                        ui.vertical_centered(|ui| {
-                            ui.label(&self.last_message);
-                            if let Some(p) = &self.last_saved_path {
-                                ui.add_space(6.0);
-                                ui.horizontal_centered(|ui| {
-                                    if ui.button("Otevřít výsledek").clicked() {
-                                        let _ = open::that(p);
-                                    }
-                                    if ui.button("Otevřít složku").clicked() {
-                                        #[cfg(target_os = "windows")]
-                                        {
-                                            let _ = std::process::Command::new("explorer")
-                                                .args(["/select,", &p.to_string_lossy()])
-                                                .spawn();
-                                        }
-                                        #[cfg(not(target_os = "windows"))]
-                                        {
-                                            if let Some(parent) = p.parent() {
-                                                let _ = open::that(parent);
-                                            }
-                                        }
-                                    }
-                                });
-                            }
-                            ui.add_space(6.0);
-                            if ui.button("OK").clicked() {
-                                close_now = true;
-                            }
-                        });
-                    });
-
-                self.result_modal_open = is_open && !close_now;
-
-                let painter = ui.painter_at(ui.max_rect());
-                painter.rect_filled(ui.max_rect(), 0.0, egui::Color32::from_black_alpha(120));
-            }
-        });
-    }
-}
-
-fn main() -> eframe::Result<()> {
-    let native_options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_inner_size([980.0, 760.0])
-            .with_min_inner_size([820.0, 560.0])
-            .with_title("Kjů ár"),
-        ..Default::default()
-    };
-    eframe::run_native(
-        "Kjů ár",
-        native_options,
-        Box::new(|_| Box::<AppState>::default()),
-    )
-}
-
-/// Pomocné metody stavu
-impl AppState {
-    fn bump_preview(&mut self) {
-        self.preview_key.clear();
-    }
-
-    fn refresh_base_dims(&mut self) {
-        self.base_dims = None;
-        if let Some(p) = &self.input_path {
-            if let Ok((w, h)) = image::image_dimensions(p) {
-                self.base_dims = Some((w, h));
-            }
-        }
-    }
-
-    fn ensure_preview(&mut self, ctx: &egui::Context) {
-        let key = self.preview_signature();
-        if self.preview_key == key {
-            return;
-        }
-        self.preview_key = key.clone();
-
-        match self.render_preview_color_image() {
-            Ok(ci) => {
-                if let Some(tex) = &mut self.preview {
-                    tex.set(ci, TextureOptions::LINEAR);
-                } else {
-                    self.preview = Some(ctx.load_texture("preview", ci, TextureOptions::LINEAR));
-                }
-                self.preview_error = None;
-            }
-            Err(e) => {
-                self.preview = None;
-                self.preview_error = Some(format!("Náhled nelze vytvořit: {e}"));
-            }
-        }
-    }
-
-    fn preview_signature(&self) -> String {
-        let in_tag = if self.bulk_mode {
-            "bulk".to_string()
-        } else {
-            self.input_path
-                .as_deref()
-                .map(|p| p.to_string_lossy().to_string())
-                .unwrap_or_else(|| "qr-only".to_string())
-        };
-        let mtime = self
-            .input_path
-            .as_deref()
-            .and_then(|p| std::fs::metadata(p).ok())
-            .and_then(|m| m.modified().ok())
-            .unwrap_or(SystemTime::UNIX_EPOCH);
-        let mticks = mtime
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .map(|d| d.as_secs())
-            .unwrap_or(0);
-
-        let [mr, mg, mb, _] = self.module_color.to_srgba_unmultiplied();
-        let [br, bg, bb, _] = self.background_color.to_srgba_unmultiplied();
-
-        format!(
-            "{in}|{mt}|{u}|{bulk}|{qr}px|{corner:?}|{ox},{oy}|{alpha}%|cut={cut}|mod={mr},{mg},{mb}|bg={br},{bg},{bb}|round={round}|fmt={fmt}",
-            in = in_tag,
-            mt = mticks,
-            u = if self.bulk_mode { self.bulk_urls.clone() } else { self.url.clone() },
-            bulk = self.bulk_mode,
-            qr = self.qr_size_px,
-            corner = self.corner,
-            ox = self.offset_x,
-            oy = self.offset_y,
-            alpha = self.qr_alpha_percent,
-            cut = self.cut_white_background,
-            round = self.rounding_percent,
-            fmt = self.out_format.ext(),
-        )
-    }
-
-    /// Náhled:
-    /// - bulk: zobrazí QR prvního neprázdného řádku
-    /// - single: pokud je vstupní obrázek, ukáže overlay; jinak ukáže samostatný QR
-    fn render_preview_color_image(&self) -> anyhow::Result<ColorImage> {
-        use anyhow::{anyhow, Context};
-
-        // vyber zdrojový text URL pro náhled
-        let preview_url = if self.bulk_mode {
-            first_nonempty_line(&self.bulk_urls).ok_or_else(|| anyhow!("Vlož aspoň jednu URL (po řádku)"))?
-        } else if self.url.trim().is_empty() {
-            return Err(anyhow!("Zadej URL pro QR"));
-        } else {
-            self.url.trim().to_string()
-        };
-
-        let [mr, mg, mb, _] = self.module_color.to_srgba_unmultiplied();
-        let bg_opt = if self.cut_white_background {
-            None
-        } else {
-            let [br, bg, bb, _] = self.background_color.to_srgba_unmultiplied();
-            Some((br, bg, bb))
-        };
-
-        if !self.bulk_mode && self.input_path.is_none() {
-            // Samostatný QR náhled (single)
-            let qr_img = build_qr_image(
-                &preview_url,
-                self.qr_size_px,
-                (mr, mg, mb),
-                bg_opt,
-                self.qr_alpha_percent,
-                self.rounding_percent,
-            )?;
-            let [w, h] = [qr_img.width() as usize, qr_img.height() as usize];
-            return Ok(ColorImage::from_rgba_unmultiplied([w, h], qr_img.as_raw()));
-        }
-
-        if self.bulk_mode {
-            // V bulk režimu vždy ukazujeme samostatný QR (podle první URL)
-            let qr_img = build_qr_image(
-                &preview_url,
-                self.qr_size_px,
-                (mr, mg, mb),
-                bg_opt,
-                self.qr_alpha_percent,
-                self.rounding_percent,
-            )?;
-            let [w, h] = [qr_img.width() as usize, qr_img.height() as usize];
-            return Ok(ColorImage::from_rgba_unmultiplied([w, h], qr_img.as_raw()));
-        }
-
-        // Overlay náhled (single + máme obrázek)
-        let in_path = self.input_path.as_ref().unwrap();
-        let base = image::open(in_path)
-            .with_context(|| format!("Nejde otevřít obrázek: {}", in_path.display()))?
-            .to_rgba8();
-
-        let (bw, bh) = base.dimensions();
-        let max_w: u32 = 1200;
-        let max_h: u32 = 1200;
-        let scale = (max_w as f32 / bw as f32)
-            .min(max_h as f32 / bh as f32)
-            .min(1.0);
-
-        let disp_w = ((bw as f32 * scale).round() as u32).max(1);
-        let disp_h = ((bh as f32 * scale).round() as u32).max(1);
-
-        let mut base_small =
-            imageops::resize(&base, disp_w, disp_h, imageops::FilterType::Triangle);
-
-        let qr_size_scaled = ((self.qr_size_px as f32 * scale).round() as u32).clamp(1, 4096);
-        let qr_img = build_qr_image(
-            &preview_url,
-            qr_size_scaled,
-            (mr, mg, mb),
-            bg_opt,
-            self.qr_alpha_percent,
-            self.rounding_percent,
-        )?;
-
-        let (qw, qh) = (qr_img.width(), qr_img.height());
-        let dx = ((self.offset_x.max(0) as f32 * scale).round() as u32).min(disp_w - 1);
-        let dy = ((self.offset_y.max(0) as f32 * scale).round() as u32).min(disp_h - 1);
-
-        let (x, y) = match self.corner {
-            Corner::Northwest => (dx, dy),
-            Corner::Northeast => (disp_w.saturating_sub(qw + dx), dy),
-            Corner::Southwest => (dx, disp_h.saturating_sub(qh + dy)),
-            Corner::Southeast => (disp_w.saturating_sub(qw + dx), disp_h.saturating_sub(qh + dy)),
-            Corner::Custom => {
-                let ax = dx.min(disp_w.saturating_sub(qw));
-                let ay = dy.min(disp_h.saturating_sub(qh));
-                (ax, ay)
-            }
-        };
-
-        imageops::overlay(&mut base_small, &qr_img, x.into(), y.into());
-
-        let [w, h] = [base_small.width() as usize, base_small.height() as usize];
-        Ok(ColorImage::from_rgba_unmultiplied([w, h], base_small.as_raw()))
-    }
-
-    fn start_job(&mut self, mode: SaveMode) {
-        use anyhow::Context;
-
-        if self.is_busy {
-            return;
-        }
-
-        // společné parametry
-        let url = self.url.clone();
-        let bulk_urls = self.bulk_urls.clone();
-        let in_path = self.input_path.clone();
-        let out_path = self.output_path.clone();
-        let export_dir = self.export_dir.clone();
-        let out_format = self.out_format;
-
-        let size = self.qr_size_px;
-        let corner = self.corner;
-        let ox = self.offset_x;
-        let oy = self.offset_y;
-
-        let alpha = self.qr_alpha_percent;
-        let cut_white = self.cut_white_background;
-        let [mr, mg, mb, _] = self.module_color.to_srgba_unmultiplied();
-        let bg_opt = if cut_white {
-            None
-        } else {
-            let [br, bg, bb, _] = self.background_color.to_srgba_unmultiplied();
-            Some((br, bg, bb))
-        };
-        let rounding = self.rounding_percent;
-
-        let (tx, rx) = channel::<JobResult>();
-        self.job_rx = Some(rx);
-        self.is_busy = true;
-
-        std::thread::spawn(move || {
-            let res = (|| -> anyhow::Result<PathBuf> {
-                match mode {
-                    SaveMode::OverlayIntoImage => {
-                        let url = url.trim();
-                        if url.is_empty() {
-                            anyhow::bail!("URL je prázdná");
-                        }
-                        let in_path = in_path.as_ref().context("Není vybrán zdrojový obrázek")?;
-                        let mut base = image::open(in_path)
-                            .with_context(|| format!("Nejde otevřít obrázek: {}", in_path.display()))?
-                            .to_rgba8();
-
-                        let qr_img = build_qr_image(url, size, (mr, mg, mb), bg_opt, alpha, rounding)?;
-
-                        let (bw, bh) = base.dimensions();
-                        let (qw, qh) = (qr_img.width(), qr_img.height());
-                        let (x, y) = match corner {
-                            Corner::Northwest => (ox.max(0) as u32, oy.max(0) as u32),
-                            Corner::Northeast => (bw.saturating_sub(qw + ox.max(0) as u32), oy.max(0) as u32),
-                            Corner::Southwest => (ox.max(0) as u32, bh.saturating_sub(qh + oy.max(0) as u32)),
-                            Corner::Southeast => (bw.saturating_sub(qw + ox.max(0) as u32), bh.saturating_sub(qh + oy.max(0) as u32)),
-                            Corner::Custom => {
-                                let ax = (ox.max(0) as u32).min(bw.saturating_sub(qw));
-                                let ay = (oy.max(0) as u32).min(bh.saturating_sub(qh));
-                                (ax, ay)
-                            }
-                        };
-
-                        imageops::overlay(&mut base, &qr_img, x.into(), y.into());
-
-                        let outp = if let Some(p) = &out_path { p.clone() } else { default_out_path(Some(in_path)).to_path_buf() };
-                        save_image_rgba(&DynamicImage::ImageRgba8(base), &outp)?;
-                        Ok(outp)
-                    }
-                    SaveMode::QrOnlySingle => {
-                        let url = url.trim();
-                        if url.is_empty() {
-                            anyhow::bail!("URL je prázdná");
-                        }
-                        let qr_img = build_qr_image(url, size, (mr, mg, mb), bg_opt, alpha, rounding)?;
-                        let outp = if let Some(p) = &out_path { p.clone() } else { default_qr_out_path() };
-                        save_qr(&qr_img, &outp, out_format, bg_opt)?;
-                        Ok(outp)
-                    }
-                    SaveMode::QrOnlyBulk => {
-                        // Rozparsuj URL po řádcích
-                        let urls: Vec<String> = bulk_urls
-                            .lines()
-                            .map(|s| s.trim())
-                            .filter(|s| !s.is_empty())
-                            .map(|s| s.to_string())
-                            .collect();
-
-                        if urls.is_empty() {
-                            anyhow::bail!("Vlož aspoň jednu URL (po řádku).");
-                        }
-
-                        // Výstupní složka
-                        let dir = export_dir.unwrap_or_else(default_bulk_dir);
-                        fs::create_dir_all(&dir)
-                            .with_context(|| format!("Nelze vytvořit složku: {}", dir.display()))?;
-
-                        let mut last = None;
-                        let mut ok = 0usize;
-                        for (i, u) in urls.iter().enumerate() {
-                            let qr_img = build_qr_image(u, size, (mr, mg, mb), bg_opt, alpha, rounding)?;
-                            let fname = make_qr_filename(i + 1, u, out_format);
-                            let path = dir.join(fname);
-                            save_qr(&qr_img, &path, out_format, bg_opt)?;
-                            ok += 1;
-                            last = Some(path);
-                        }
-
-                        let msg_path = last.unwrap_or(dir.clone());
-                        println!("Hotovo: {} souborů do {}", ok, dir.display());
-                        Ok(msg_path)
-                    }
-                }
-            })();
-
-            let _ = match res {
-                Ok(p) => tx.send(JobResult::Ok(p)),
-                Err(e) => tx.send(JobResult::Err(e.to_string())),
-            };
-        });
-    }
-}
-
-/// Uloží obecný RGBA obrázek podle přípony (png/jpg/tif) – pro overlay.
-fn save_image_rgba(img: &DynamicImage, outp: &Path) -> anyhow::Result<()> {
-    use anyhow::Context;
-    let ext = outp.extension().and_then(|e| e.to_str()).unwrap_or("").to_ascii_lowercase();
-    match ext.as_str() {
-        "jpg" | "jpeg" => {
-            let mut out = std::fs::File::create(outp)?;
-            let rgb = img.to_rgb8();
-            let mut encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut out, 92);
-            encoder
-                .encode_image(&DynamicImage::ImageRgb8(rgb))
-                .context("JPEG encode selhal")?;
-        }
-        "png" | "tif" | "tiff" | "" => {
-            img.save(outp).context("Uložení obrázku selhalo")?;
-        }
-        other => anyhow::bail!("Nepodporovaná přípona: .{other} (použij .jpg/.jpeg/.png/.tif/.tiff)"),
-    }
-    Ok(())
-}
-
-/// Uloží samostatný QR (RGBA) ve zvoleném formátu.
-/// - PNG/TIFF: zachová alfu.
-/// - JPEG: slije alfu na pozadí (bílá pokud `bg_opt=None`, jinak zadaná barva).
-fn save_qr(qr: &RgbaImage, outp: &Path, fmt: OutputFormat, bg_opt: Option<(u8, u8, u8)>) -> anyhow::Result<()> {
-    use anyhow::Context;
-    match fmt {
-        OutputFormat::Png | OutputFormat::Tiff => {
-            DynamicImage::ImageRgba8(qr.clone()).save(outp).context("Uložení obrázku selhalo")?;
-        }
-        OutputFormat::Jpeg => {
-            let bg = bg_opt.unwrap_or((255, 255, 255));
-            let rgb = flatten_rgba_to_rgb(qr, bg);
-            let mut out = std::fs::File::create(outp)?;
-            let mut encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut out, 92);
-            encoder
-                .encode_image(&DynamicImage::ImageRgb8(rgb))
-                .context("JPEG encode selhal")?;
-        }
-    }
-    Ok(())
-}
-
-/// Vykreslí QR kód s barvou modulů, volitelnou barvou pozadí, průhledností a zaoblením.
-/// - `bg_rgb = None` → pozadí QR je plně průhledné (ekvivalent „Odstranit pozadí“)
-/// - `rounding_percent` v rozsahu 0–50 (% z velikosti modulu)
-fn build_qr_image(
-    url: &str,
-    size_px: u32,
-    mod_rgb: (u8, u8, u8),
-    bg_rgb: Option<(u8, u8, u8)>,
-    alpha_percent: u8,
-    rounding_percent: u8,
-) -> anyhow::Result<RgbaImage> {
-    use anyhow::Context;
-
-    let code = QrCode::new(url.as_bytes()).context("Neplatné URL pro QR?")?;
-    let width_mod = code.width() as u32;
-    let quiet_zone_mod: u32 = 4; // doporučené minimum
-    let total_mod = width_mod + 2 * quiet_zone_mod;
-
-    // supersampling pro hladké zaoblení
-    let ss: u32 = 4;
-    let target_ss = size_px.max(total_mod) * ss;
-    let module_ss = (target_ss / total_mod).max(1);
-    let canvas_ss = module_ss * total_mod;
-
-    let a = ((alpha_percent as u16 * 255) / 100) as u8;
-    let mod_rgba = Rgba([mod_rgb.0, mod_rgb.1, mod_rgb.2, a]);
-    let bg_rgba = match bg_rgb {
-        Some(c) => Rgba([c.0, c.1, c.2, a]),
-        None => Rgba([0, 0, 0, 0]),
-    };
-
-    let mut img = RgbaImage::from_pixel(canvas_ss, canvas_ss, bg_rgba);
-
-    // přepočet zaoblení na pixely v supersamplovaném prostoru
-    let mut r = (module_ss as f32 * (rounding_percent as f32 / 100.0)).round() as i32;
-    let half = (module_ss / 2) as i32;
-    if r > half {
-        r = half; // max 50 % (bez přesahů)
-    }
-
-    // vykresli moduly
-    for y in 0..width_mod {
-        for x in 0..width_mod {
-            if code[(x as usize, y as usize)] == QrColor::Dark {
-                let x0 = ((x + quiet_zone_mod) * module_ss) as i32;
-                let y0 = ((y + quiet_zone_mod) * module_ss) as i32;
-                let w = module_ss as i32;
-                let h = w;
-
-                if r <= 0 {
-                    draw_filled_rect_mut(&mut img, Rect::at(x0, y0).of_size(w as u32, h as u32), mod_rgba);
-                } else {
-                    // středové pruhy
-                    if w - 2 * r > 0 {
-                        draw_filled_rect_mut(&mut img, Rect::at(x0 + r, y0).of_size((w - 2 * r) as u32, h as u32), mod_rgba);
-                        draw_filled_rect_mut(&mut img, Rect::at(x0, y0 + r).of_size(w as u32, (h - 2 * r) as u32), mod_rgba);
-                    }
-
-                    // čtyři kruhy vnitřních rohů
-                    let cx1 = x0 + r;
-                    let cy1 = y0 + r;
-                    let cx2 = x0 + w - r - 1;
-                    let cy2 = y0 + h - r - 1;
-                    draw_filled_circle_mut(&mut img, (cx1, cy1), r, mod_rgba);
-                    draw_filled_circle_mut(&mut img, (cx2, cy1), r, mod_rgba);
-                    draw_filled_circle_mut(&mut img, (cx1, cy2), r, mod_rgba);
-                    draw_filled_circle_mut(&mut img, (cx2, cy2), r, mod_rgba);
-                }
-            }
-        }
-    }
-
-    // downscale na cílovou velikost (vyhlazení hran)
-    let final_img = imageops::resize(&img, size_px, size_px, imageops::FilterType::Lanczos3);
-    Ok(final_img)
-}
-
-/// Slije RGBA na zadané RGB pozadí (pro JPEG).
-fn flatten_rgba_to_rgb(src: &RgbaImage, bg: (u8, u8, u8)) -> RgbImage {
-    let (w, h) = src.dimensions();
-    let mut dst = RgbImage::new(w, h);
-    for (x, y, p) in src.enumerate_pixels() {
-        let (sr, sg, sb, sa) = (p[0] as u16, p[1] as u16, p[2] as u16, p[3] as u16);
-        let a = sa; // 0..255
-        let ir = (sr * a + (bg.0 as u16) * (255 - a) + 127) / 255;
-        let ig = (sg * a + (bg.1 as u16) * (255 - a) + 127) / 255;
-        let ib = (sb * a + (bg.2 as u16) * (255 - a) + 127) / 255;
-        dst.put_pixel(x, y, Rgb([ir as u8, ig as u8, ib as u8]));
-    }
-    dst
-}
-
-fn first_nonempty_line(s: &str) -> Option<String> {
-    for line in s.lines() {
-        let t = line.trim();
-        if !t.is_empty() {
-            return Some(t.to_string());
-        }
-    }
-    None
-}
-
-fn default_out_path(in_path: Option<&PathBuf>) -> PathBuf {
-    match in_path {
-        Some(p) => {
-            let parent = p.parent().unwrap_or_else(|| Path::new("."));
-            let stem = p.file_stem().unwrap_or_default().to_string_lossy();
-            let ext = p.extension().and_then(|e| e.to_str()).unwrap_or("png");
-            parent.join(format!("out_{}.{}", stem, ext))
-        }
-        None => default_qr_out_path(),
-    }
-}
-
-fn default_qr_out_path() -> PathBuf {
-    PathBuf::from("qr.png")
-}
-
-fn default_bulk_dir() -> PathBuf {
-    PathBuf::from("qr_export")
-}
-
-fn make_qr_filename(index1: usize, url: &str, fmt: OutputFormat) -> String {
-    let slug = make_slug_from_url(url);
-    let hash10 = sha1_hex10(url);
-    let base = if slug.is_empty() {
-        format!("qr_{:03}_{}", index1, hash10)
-    } else {
-        format!("qr_{:03}_{}_{}", index1, slug, hash10)
-    };
-    format!("{base}.{}", fmt.ext())
-}
-
-fn sha1_hex10(s: &str) -> String {
-    let mut hasher = Sha1::new();
-    hasher.update(s.as_bytes());
-    let bytes = hasher.finalize();
-    let mut out = String::with_capacity(10);
-    for b in bytes.iter().take(5) {
-        out.push_str(&format!("{:02x}", b));
-    }
-    out
-}
-
-fn make_slug_from_url(url: &str) -> String {
-    // jednoduchý slug: host + poslední segment cesty
-    let u = url.trim().trim_end_matches('/');
-    let host = u.split("://").nth(1).unwrap_or(u);
-    let host = host.split('/').next().unwrap_or("");
-    let last = u.rsplit('/').next().unwrap_or("");
-    let mut s = String::new();
-    if !host.is_empty() {
-        s.push_str(&sanitize_for_filename(host));
-    }
-    if !last.is_empty() && last != host {
-        if !s.is_empty() {
-            s.push('_');
-        }
-        s.push_str(&sanitize_for_filename(last));
-    }
-    if s.len() > 40 {
-        s.truncate(40);
-    }
-    s.trim_matches('_').to_string()
-}
-
-fn sanitize_for_filename(s: &str) -> String {
-    let mut out = String::with_capacity(s.len());
-    for ch in s.chars() {
-        if ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' {
-            out.push(ch);
-        } else if ch.is_ascii() {
-            out.push('-');
-        } // ne-ASCII: vynecháme
-    }
-    // sloučit víc '-' do jednoho
-    let mut compact = String::with_capacity(out.len());
-    let mut prev_dash = false;
-    for c in out.chars() {
-        if c == '-' {
-            if !prev_dash {
-                compact.push(c);
-            }
-            prev_dash = true;
-        } else {
-            compact.push(c);
-            prev_dash = false;
-        }
-    }
-    compact.trim_matches('-').to_string()
-}
-
-fn shorten(p: &Path) -> String {
-    let cwd = std::env::current_dir().ok();
-    if let Some(cwd) = cwd {
-        if let Some(rel) = pathdiff::diff_paths(p, cwd) {
-            return rel.to_string_lossy().to_string();
-        }
-    }
-    p.to_string_lossy().to_string()
-}
+#![cfg_attr(all(target_os = "windows", not(debug_assertions)), windows_subsystem = "windows")]
+
+mod cli;
+mod payload_form;
+mod presets;
+
+use clap::Parser;
+use eframe::egui;
+use egui::{Align, Color32, ColorImage, ComboBox, Layout, TextEdit, TextureHandle, TextureOptions, Vec2};
+use image::imageops;
+use kju_ar::{
+    add_caption, build_qr_image, default_bulk_dir, default_out_path, default_qr_out_path, export_bulk_with_progress,
+    load_logo, min_ec_for_logo, overlay_into_image, overlay_margin_px, parse_url_lines, qr_version, raster_size_px,
+    save_qr, verify_overlay, verify_qr_file, BulkItem, BulkOptions, BulkProgress, BulkReport, Caption, ColumnMapping,
+    Corner, EcLevel, EyeShape, EyeStyle, Gradient, GradientKind, GradientStop, ModuleShape, OutputFormat, OverlaySpec,
+    QrStyle, Table, Utm,
+};
+use payload_form::{PayloadForms, PayloadKind};
+use rfd::FileDialog;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+enum JobResult {
+    Ok(PathBuf),
+    Unreadable(PathBuf, String), // uloženo, ale ověření QR nepřečetlo
+    Bulk(BulkReport),
+    Err(String),
+}
+
+/// Nad touto verzí (57×57 modulů) je QR na vizitce nebo letáku už těžko čitelný.
+const DENSE_QR_VERSION: u8 = 10;
+
+/// Zpráva z background jobu – průběh hromadného exportu, nakonec výsledek.
+enum JobMsg {
+    Progress(BulkProgress),
+    Done(JobResult),
+}
+
+/// Tabulka pro hromadný režim a mapování jejích sloupců.
+struct TableImport {
+    path: PathBuf,
+    table: Table,
+    mapping: ColumnMapping,
+    confirmed: bool,       // false => zobrazit krok mapování
+    error: Option<String>, // chyba při potvrzení mapování
+}
+
+#[derive(Clone, Copy)]
+enum SaveMode {
+    OverlayIntoImage,
+    QrOnlySingle,
+    QrOnlyBulk,
+}
+
+/// Stav aplikace; vzhled QR, formát a cesty se ukládají mezi spuštěními
+/// (eframe persistence), texty URL a běhový stav ne.
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+struct AppState {
+    // Režimy
+    #[serde(skip)]
+    bulk_mode: bool,
+
+    // URL vstup
+    #[serde(skip)]
+    url: String,          // single
+    #[serde(skip)]
+    payload: PayloadForms, // single – typ obsahu a formuláře (Wi-Fi, …)
+    #[serde(skip)]
+    bulk_urls: String,    // multi – po řádcích
+    #[serde(skip)]
+    table_import: Option<TableImport>, // CSV/TSV místo `bulk_urls`
+
+    // Volby výstupu
+    output_path: Option<PathBuf>,   // single QR i overlay
+    export_dir: Option<PathBuf>,    // složka pro hromadné
+    out_format: OutputFormat,
+    pdf_size_mm: f32, // fyzická velikost QR v PDF (s `print_exact` i rastru)
+    print_exact: bool, // rastr odvozený z `pdf_size_mm` a `dpi`, DPI zapsané v souboru
+    print_inches: bool, // fyzickou velikost zadávat v palcích
+    dpi: u32,
+    quiet_zone_mod: u32, // okraj kolem symbolu v modulech (0–10)
+    bulk_workers: usize, // vlákna pro hromadný export (0 = všechna jádra)
+    verify_output: bool, // uložený QR zpětně dekódovat
+    utm_enabled: bool,   // připojit k odkazům `utm`
+    utm: Utm,
+
+    // Vstupní obrázek (jen overlay)
+    input_path: Option<PathBuf>,
+    #[serde(skip)]
+    base_dims: Option<(u32, u32)>,
+
+    // QR parametry
+    qr_size_px: u32,
+    #[serde(with = "kju_ar::ec_level_serde")]
+    ec_level: EcLevel, // úroveň korekce chyb (L/M/Q/H)
+    corner: Corner,
+    offset_x: i32,
+    offset_y: i32,
+
+    // Vzhled QR
+    rounding_percent: u8,       // 0–50 % z velikosti modulu
+    module_shape: ModuleShape,  // tečky, kosočtverce, pruhy, …
+    module_color: Color32,      // barva „tmavých“ modulů
+    gradient_enabled: bool,     // moduly barevným přechodem místo `module_color`
+    gradient: Gradient,
+    eyes_custom: bool,          // rohové značky vlastním tvarem a barvou
+    eye_frame: EyeShape,
+    eye_pupil: EyeShape,
+    eye_frame_color: Color32,
+    eye_pupil_color: Color32,
+    background_color: Color32,  // barva pozadí (použije se, když není „Odstranit pozadí“)
+    qr_alpha_percent: u8,       // 0–100 %
+    cut_white_background: bool, // true => pozadí QR bude plně průhledné
+
+    // Logo uprostřed QR
+    logo_path: Option<PathBuf>,
+    logo_size_percent: u8, // 10–30 % šířky symbolu
+    logo_knockout: bool,   // vyčistit moduly pod logem
+
+    // Předvolby vzhledu
+    presets: Vec<presets::Preset>,
+    #[serde(skip)]
+    preset_name: String, // jméno pro „Uložit předvolbu“
+
+    // Výsledky / status
+    #[serde(skip)]
+    last_message: String,
+    #[serde(skip)]
+    last_saved_path: Option<PathBuf>,
+    #[serde(skip)]
+    last_report: Option<BulkReport>, // výsledek posledního hromadného exportu
+    #[serde(skip)]
+    last_unreadable: bool, // ověření našlo nečitelný QR
+
+    // Náhled
+    #[serde(skip)]
+    preview: Option<TextureHandle>,
+    #[serde(skip)]
+    preview_key: String,
+    #[serde(skip)]
+    preview_error: Option<String>,
+
+    // Asynchronní uložení
+    #[serde(skip)]
+    is_busy: bool,
+    #[serde(skip)]
+    job_rx: Option<Receiver<JobMsg>>,
+    #[serde(skip)]
+    job_cancel: Arc<AtomicBool>,
+    #[serde(skip)]
+    job_started: Option<Instant>,
+    #[serde(skip)]
+    job_progress: Option<BulkProgress>,
+
+    // Modální okno s výsledkem
+    #[serde(skip)]
+    result_modal_open: bool,
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        Self {
+            bulk_mode: false,
+
+            url: "".to_owned(),
+            payload: PayloadForms::default(),
+            bulk_urls: "".to_owned(),
+            table_import: None,
+
+            output_path: None,
+            export_dir: None,
+            out_format: OutputFormat::Png,
+            pdf_size_mm: 50.0,
+            print_exact: false,
+            print_inches: false,
+            dpi: 300,
+            quiet_zone_mod: 4,
+            bulk_workers: 0,
+            verify_output: false,
+            utm_enabled: false,
+            utm: Utm::default(),
+
+            input_path: None,
+            base_dims: None,
+
+            qr_size_px: 160,
+            ec_level: EcLevel::M,
+            corner: Corner::Southeast,
+            offset_x: 10,
+            offset_y: 10,
+
+            rounding_percent: 0,
+            module_shape: ModuleShape::Square,
+            module_color: Color32::BLACK,
+            gradient_enabled: false,
+            gradient: Gradient::even(GradientKind::Linear { angle_deg: 45.0 }, &[(0, 0, 0), (29, 78, 216)]),
+            eyes_custom: false,
+            eye_frame: EyeShape::Square,
+            eye_pupil: EyeShape::Square,
+            eye_frame_color: Color32::BLACK,
+            eye_pupil_color: Color32::BLACK,
+            background_color: Color32::WHITE,
+            qr_alpha_percent: 85,
+            cut_white_background: true,
+
+            logo_path: None,
+            logo_size_percent: 20,
+            logo_knockout: true,
+
+            presets: Vec::new(),
+            preset_name: String::new(),
+
+            last_message: String::new(),
+            last_saved_path: None,
+            last_report: None,
+            last_unreadable: false,
+
+            preview: None,
+            preview_key: String::new(),
+            preview_error: None,
+
+            is_busy: false,
+            job_rx: None,
+            job_cancel: Arc::default(),
+            job_started: None,
+            job_progress: None,
+
+            result_modal_open: false,
+        }
+    }
+}
+
+impl eframe::App for AppState {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, eframe::APP_KEY, self);
+    }
+
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // Poll průběhu a výsledků background jobu
+        let mut done = None;
+        if let Some(rx) = &self.job_rx {
+            for msg in rx.try_iter() {
+                match msg {
+                    JobMsg::Progress(p) => self.job_progress = Some(p),
+                    JobMsg::Done(res) => done = Some(res),
+                }
+            }
+        }
+        if let Some(msg) = done {
+            self.is_busy = false;
+            self.job_rx = None;
+            self.job_progress = None;
+            self.last_report = None;
+            self.last_unreadable = false;
+            match msg {
+                JobResult::Ok(path) => {
+                    self.last_saved_path = Some(path.clone());
+                    self.last_message = format!("Uloženo: {}", path.display());
+                }
+                JobResult::Unreadable(path, e) => {
+                    self.last_saved_path = Some(path.clone());
+                    self.last_message = format!("Uloženo: {}\nPozor – {e}", path.display());
+                    self.last_unreadable = true;
+                }
+                JobResult::Bulk(report) => {
+                    let (ok, failed) = (report.ok_count(), report.failed_count());
+                    self.last_saved_path =
+                        (ok > 0).then(|| report.last_saved().unwrap_or(&report.dir).to_path_buf());
+                    self.last_message = if failed == 0 {
+                        format!("Uloženo {ok} souborů do {}", report.dir.display())
+                    } else {
+                        format!("Uloženo {ok} souborů do {}, {failed} selhalo.", report.dir.display())
+                    };
+                    let unreadable = report.unreadable_count();
+                    if unreadable > 0 {
+                        self.last_message = format!("{} Nečitelných QR: {unreadable}.", self.last_message);
+                        self.last_unreadable = true;
+                    }
+                    if report.cancelled {
+                        self.last_message =
+                            format!("Přerušeno. {} Nezpracováno: {}.", self.last_message, report.skipped_count());
+                    }
+                    self.last_report = Some(report);
+                }
+                JobResult::Err(e) => {
+                    self.last_saved_path = None;
+                    self.last_message = format!("Chyba: {e}");
+                }
+            }
+            self.result_modal_open = true;
+        }
+
+        egui::TopBottomPanel::top("top").show(ctx, |ui| {
+            ui.with_layout(Layout::left_to_right(Align::Center), |ui| {
+                ui.heading("Kjů ár");
+                ui.add_space(12.0);
+                ui.label("Vlož QR do obrázku nebo hromadně ulož samostatné QR.");
+            });
+        });
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.spacing_mut().item_spacing = egui::vec2(10.0, 10.0);
+
+            ui.columns(2, |cols| {
+                // === LEVÝ SLOUPEC – ovládání ===
+                cols[0].vertical(|ui| {
+                    let mapping_open = self.table_import.as_ref().is_some_and(|t| !t.confirmed);
+                    ui.add_enabled_ui(!self.is_busy && !self.result_modal_open && !mapping_open, |ui| {
+                        ui.group(|ui| {
+                            ui.horizontal(|ui| {
+                                ui.label("Režim:");
+                                ui.selectable_value(&mut self.bulk_mode, false, "Jednotlivě");
+                                ui.selectable_value(&mut self.bulk_mode, true, "Hromadně (URL po řádcích)");
+                            });
+                        });
+
+                        ui.group(|ui| {
+                            if self.bulk_mode {
+                                ui.label("Vlož víc URL – každé na samostatný řádek:");
+                                let from_table = self.table_import.is_some();
+                                if ui
+                                    .add_enabled(
+                                        !from_table,
+                                        egui::TextEdit::multiline(&mut self.bulk_urls)
+                                            .hint_text("https://...\nhttps://...\n...")
+                                            .desired_rows(6)
+                                            .desired_width(f32::INFINITY),
+                                    )
+                                    .changed()
+                                {
+                                    self.bump_preview();
+                                }
+                                ui.horizontal(|ui| {
+                                    if ui.button("Načíst tabulku (CSV/TSV)…").clicked()
+                                        && let Some(p) = FileDialog::new()
+                                            .add_filter("Tabulka", &["csv", "tsv", "txt"])
+                                            .pick_file()
+                                    {
+                                        match Table::open(&p) {
+                                            Ok(table) => {
+                                                let mapping = ColumnMapping::guess(&table);
+                                                self.table_import =
+                                                    Some(TableImport { path: p, table, mapping, confirmed: false, error: None });
+                                            }
+                                            Err(e) => {
+                                                self.last_message = format!("Chyba: {e}");
+                                            }
+                                        }
+                                        self.bump_preview();
+                                    }
+                                    if let Some(t) = &self.table_import {
+                                        ui.monospace(format!("Tabulka: {}", shorten(&t.path)));
+                                        if ui.button("Upravit sloupce").clicked() {
+                                            self.table_import.as_mut().unwrap().confirmed = false;
+                                        }
+                                        if ui.button("Zrušit tabulku").clicked() {
+                                            self.table_import = None;
+                                            self.bump_preview();
+                                        }
+                                    }
+                                });
+                            } else {
+                                ui.horizontal(|ui| {
+                                    ui.label("Typ obsahu:");
+                                    ComboBox::from_id_source("payload_kind")
+                                        .selected_text(self.payload.kind.label())
+                                        .show_ui(ui, |ui| {
+                                            for k in PayloadKind::ALL {
+                                                ui.selectable_value(&mut self.payload.kind, k, k.label());
+                                            }
+                                        });
+                                });
+                                if self.payload.kind == PayloadKind::Url {
+                                    ui.label("Odkaz (URL) pro QR kód:");
+                                    if ui
+                                        .add(
+                                            TextEdit::singleline(&mut self.url)
+                                                .hint_text("https://...")
+                                                .clip_text(true)
+                                                .desired_width(f32::INFINITY),
+                                        )
+                                        .changed()
+                                    {
+                                        self.bump_preview();
+                                    }
+                                } else {
+                                    if self.payload.ui(ui) {
+                                        self.bump_preview();
+                                    }
+                                    // zakódovaný text – kontrola escapování
+                                    match self.content() {
+                                        Ok(text) => {
+                                            ui.add(egui::Label::new(egui::RichText::new(&text).monospace().weak()).wrap(true));
+                                            self.qr_version_hint(ui, &text);
+                                        }
+                                        Err(e) => {
+                                            ui.colored_label(egui::Color32::RED, e.to_string());
+                                        }
+                                    }
+                                }
+                            }
+                        });
+
+                        // Parametry kampaně – jen pro odkazy
+                        if self.bulk_mode || self.payload.kind == PayloadKind::Url {
+                            ui.group(|ui| self.utm_ui(ui));
+                        }
+
+                        // Soubory / výstup
+                        ui.group(|ui| {
+                            ui.label("Výstup:");
+                            if self.bulk_mode {
+                                if ui.button("Zvolit výstupní složku…").clicked()
+                                    && let Some(dir) = FileDialog::new().pick_folder()
+                                {
+                                    self.export_dir = Some(dir);
+                                }
+                                ui.monospace(format!(
+                                    "Složka: {}",
+                                    self.export_dir
+                                        .as_deref()
+                                        .map(shorten)
+                                        .unwrap_or_else(|| format!("<automaticky: {}>", default_bulk_dir().display()))
+                                ));
+                                ui.horizontal(|ui| {
+                                    ui.label("Formát:");
+                                    ComboBox::from_id_source("fmt")
+                                        .selected_text(format_label(self.out_format))
+                                        .show_ui(ui, |ui| {
+                                            for f in OutputFormat::ALL {
+                                                ui.selectable_value(&mut self.out_format, f, format_label(f));
+                                            }
+                                        });
+                                });
+                                ui.horizontal(|ui| {
+                                    ui.label("Vlákna:");
+                                    ui.add(egui::DragValue::new(&mut self.bulk_workers).clamp_range(0..=64))
+                                        .on_hover_text("Kolik QR se vykresluje současně; 0 = všechna jádra.");
+                                    if self.bulk_workers == 0 {
+                                        ui.weak("automaticky");
+                                    }
+                                });
+                            } else {
+                                if ui.button("Zvolit výstupní soubor…").clicked() {
+                                    // návrh názvu: podle vstupu, jinak qr.png
+                                    let suggested = if self.input_path.is_some() {
+                                        default_out_path(self.input_path.as_deref())
+                                    } else {
+                                        default_qr_out_path()
+                                    };
+                                    if let Some(p) = FileDialog::new()
+                                        .set_file_name(
+                                            suggested
+                                                .file_name()
+                                                .unwrap_or_default()
+                                                .to_string_lossy(),
+                                        )
+                                        .save_file()
+                                    {
+                                        self.output_path = Some(p);
+                                    }
+                                }
+                                ui.monospace(format!(
+                                    "Soubor: {}",
+                                    self.output_path
+                                        .as_deref()
+                                        .map(shorten)
+                                        .unwrap_or_else(|| {
+                                            if self.input_path.is_some() {
+                                                "<automaticky: out_<původní>.jpg/png/tif>".to_string()
+                                            } else {
+                                                "<automaticky: qr.png>".to_string()
+                                            }
+                                        })
+                                ));
+                            }
+
+                            // Fyzická velikost – v PDF vždy (samostatný QR = strana, overlay = měřítko
+                            // obrázku), s přesnou tiskovou velikostí i pro rastr
+                            let pdf_out = if self.bulk_mode {
+                                self.out_format == OutputFormat::Pdf
+                            } else {
+                                self.output_path.as_deref().and_then(OutputFormat::from_path) == Some(OutputFormat::Pdf)
+                            };
+                            if ui
+                                .checkbox(&mut self.print_exact, "Přesná tisková velikost (i PNG/TIFF/JPG)")
+                                .on_hover_text(
+                                    "Velikost v px se odvodí z fyzické velikosti a DPI tak, aby každý modul měl \
+                                     celý počet pixelů; rozlišení se zapíše do souboru.",
+                                )
+                                .changed()
+                            {
+                                self.bump_preview();
+                            }
+                            ui.add_enabled_ui(pdf_out || self.print_exact, |ui| {
+                                self.print_size_ui(ui);
+                            });
+                            ui.checkbox(&mut self.verify_output, "Ověřit čitelnost uloženého QR")
+                                .on_hover_text("Uložený QR se zpětně dekóduje a porovná se zadaným obsahem.");
+                        });
+
+                        // Vstupní obrázek (jen mimo hromadný režim)
+                        ui.add_enabled_ui(!self.bulk_mode, |ui| {
+                            ui.group(|ui| {
+                                ui.label("Zdrojový obrázek (pro vložení QR):");
+                                if ui.button("Vybrat zdrojový obrázek…").clicked()
+                                    && let Some(p) = FileDialog::new()
+                                        .add_filter("Obrázky", &["jpg", "jpeg", "png", "tif", "tiff"])
+                                        .pick_file()
+                                {
+                                    self.input_path = Some(p);
+                                    self.refresh_base_dims();
+                                    self.bump_preview();
+                                }
+                                ui.monospace(format!(
+                                    "Zdroj: {}",
+                                    self.input_path
+                                        .as_deref()
+                                        .map(shorten)
+                                        .unwrap_or_else(|| "<není vybráno>".to_string())
+                                ));
+                            });
+                        });
+
+                        // Předvolby vzhledu
+                        ui.group(|ui| {
+                            ui.horizontal(|ui| {
+                                ui.label("Předvolba:");
+                                let mut picked = None;
+                                ComboBox::from_id_source("preset")
+                                    .selected_text(if self.preset_name.is_empty() { "—" } else { self.preset_name.as_str() })
+                                    .show_ui(ui, |ui| {
+                                        for (i, p) in self.presets.iter().enumerate() {
+                                            if ui.selectable_label(p.name == self.preset_name, &p.name).clicked() {
+                                                picked = Some(i);
+                                            }
+                                        }
+                                    });
+                                if let Some(i) = picked {
+                                    let p = self.presets[i].clone();
+                                    self.apply_preset(&p);
+                                }
+                                let exists = self.presets.iter().any(|p| p.name == self.preset_name);
+                                if ui.add_enabled(exists, egui::Button::new("Smazat")).clicked() {
+                                    self.presets.retain(|p| p.name != self.preset_name);
+                                    self.preset_name.clear();
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                ui.add(
+                                    TextEdit::singleline(&mut self.preset_name)
+                                        .hint_text("název předvolby")
+                                        .desired_width(180.0),
+                                );
+                                let name = self.preset_name.trim().to_string();
+                                if ui.add_enabled(!name.is_empty(), egui::Button::new("Uložit předvolbu")).clicked() {
+                                    let p = self.current_preset(name.clone());
+                                    presets::merge(&mut self.presets, vec![p]);
+                                    self.preset_name = name;
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                if ui.button("Importovat…").clicked()
+                                    && let Some(path) = FileDialog::new()
+                                        .add_filter("Předvolby", &["json", "toml"])
+                                        .pick_file()
+                                {
+                                    self.last_message = match presets::import(&path) {
+                                        Ok(new) => {
+                                            let n = presets::merge(&mut self.presets, new);
+                                            format!("Načteno předvoleb: {n}")
+                                        }
+                                        Err(e) => format!("Chyba: {e:#}"),
+                                    };
+                                }
+                                if ui.add_enabled(!self.presets.is_empty(), egui::Button::new("Exportovat…")).clicked()
+                                    && let Some(path) = FileDialog::new()
+                                        .add_filter("JSON", &["json"])
+                                        .add_filter("TOML", &["toml"])
+                                        .set_file_name("predvolby.json")
+                                        .save_file()
+                                {
+                                    self.last_message = match presets::export(&path, &self.presets) {
+                                        Ok(()) => format!("Předvolby uloženy: {}", path.display()),
+                                        Err(e) => format!("Chyba: {e:#}"),
+                                    };
+                                }
+                            });
+                        });
+
+                        ui.group(|ui| {
+                            ui.label("QR kód:");
+
+                            // Velikost
+                            // (s přesnou tiskovou velikostí se odvodí z fyzické velikosti a DPI)
+                            if ui
+                                .add_enabled(
+                                    !self.print_exact,
+                                    egui::Slider::new(&mut self.qr_size_px, 64..=2048)
+                                        .text("Velikost")
+                                        .suffix(" px")
+                                        .step_by(1.0),
+                                )
+                                .changed()
+                            {
+                                self.bump_preview();
+                            }
+
+                            // Tichá zóna – světlý okraj kolem symbolu
+                            if ui
+                                .add(
+                                    egui::Slider::new(&mut self.quiet_zone_mod, 0..=10)
+                                        .text("Tichá zóna")
+                                        .suffix(" mod.")
+                                        .step_by(1.0),
+                                )
+                                .changed()
+                            {
+                                self.bump_preview();
+                            }
+                            if let Some(w) = self.qr_style().quiet_zone_warning() {
+                                ui.colored_label(Color32::from_rgb(217, 119, 6), format!("⚠ {w}"));
+                            }
+
+                            // Korekce chyb
+                            ui.horizontal(|ui| {
+                                ui.label("Korekce chyb:");
+                                if let Some(ec) = self.required_ec() {
+                                    // formát obsahu (EPC) úroveň předepisuje
+                                    ui.label(format!("{} – povinná pro zvolený typ obsahu", ec_level_label(ec)));
+                                    return;
+                                }
+                                ComboBox::from_id_source("ec_level")
+                                    .selected_text(ec_level_label(self.ec_level))
+                                    .show_ui(ui, |ui| {
+                                        for ec in [EcLevel::L, EcLevel::M, EcLevel::Q, EcLevel::H] {
+                                            if ui.selectable_value(&mut self.ec_level, ec, ec_level_label(ec)).changed() {
+                                                self.bump_preview();
+                                            }
+                                        }
+                                    });
+                            });
+
+                            // Tvar modulů
+                            ui.horizontal(|ui| {
+                                ui.label("Tvar modulů:");
+                                ComboBox::from_id_source("module_shape")
+                                    .selected_text(module_shape_label(self.module_shape))
+                                    .show_ui(ui, |ui| {
+                                        for shape in MODULE_SHAPES {
+                                            if ui
+                                                .selectable_value(&mut self.module_shape, shape, module_shape_label(shape))
+                                                .changed()
+                                            {
+                                                self.bump_preview();
+                                            }
+                                        }
+                                    });
+                            });
+
+                            // Zaoblení rohů (0–50 % modulu) – jen čtvercové moduly
+                            if ui
+                                .add_enabled(
+                                    self.module_shape == ModuleShape::Square,
+                                    egui::Slider::new(&mut self.rounding_percent, 0..=50)
+                                        .text("Zaoblení rohů")
+                                        .suffix(" % modulu")
+                                        .step_by(1.0),
+                                )
+                                .changed()
+                            {
+                                self.bump_preview();
+                            }
+
+                            // Barva modulů
+                            ui.horizontal(|ui| {
+                                ui.label("Barva modulů:");
+                                let mut c = self.module_color;
+                                if egui::color_picker::color_edit_button_srgba(
+                                    ui,
+                                    &mut c,
+                                    egui::color_picker::Alpha::Opaque,
+                                )
+                                .changed()
+                                {
+                                    self.module_color = c;
+                                    self.bump_preview();
+                                }
+                            });
+
+                            // Barevný přechod modulů
+                            self.gradient_ui(ui);
+
+                            // Rohové značky (oka) – vlastní tvar a barva
+                            self.eyes_ui(ui);
+
+                            if let Some(w) = self.qr_style().contrast_warning() {
+                                ui.colored_label(Color32::from_rgb(217, 119, 6), format!("⚠ {w}"));
+                            }
+
+                            // Barva pozadí (použije se, když není „Odstranit pozadí“)
+                            ui.horizontal(|ui| {
+                                ui.label("Pozadí QR:");
+                                let mut bg = self.background_color;
+                                let mut changed = false;
+                                ui.add_enabled_ui(!self.cut_white_background, |ui| {
+                                    if egui::color_picker::color_edit_button_srgba(
+                                        ui,
+                                        &mut bg,
+                                        egui::color_picker::Alpha::Opaque,
+                                    )
+                                    .changed()
+                                    {
+                                        changed = true;
+                                    }
+                                });
+                                if changed {
+                                    self.background_color = bg;
+                                    self.bump_preview();
+                                }
+                                if self.cut_white_background {
+                                    ui.small(" (nepoužije se při zapnutém „Odstranit pozadí“)");
+                                }
+                            });
+
+                            // Průhlednost QR – invertované ovládání (→ vpravo = 0 %, vlevo = 100 %)
+                            {
+                                let mut inv_alpha = 100 - self.qr_alpha_percent;
+                                let resp = ui.add(
+                                    egui::Slider::new(&mut inv_alpha, 0..=100)
+                                        .text("Průhlednost QR")
+                                        .suffix(" %")
+                                        .step_by(1.0),
+                                );
+                                if resp.changed() {
+                                    self.qr_alpha_percent = 100 - inv_alpha;
+                                    self.bump_preview();
+                                }
+                            }
+
+                            // „Odstranit pozadí“ (pozadí QR)
+                            if ui
+                                .checkbox(&mut self.cut_white_background, "Odstranit pozadí (průhledné pozadí)")
+                                .changed()
+                            {
+                                self.bump_preview();
+                            }
+
+                            ui.separator();
+
+                            // Logo uprostřed QR
+                            ui.horizontal(|ui| {
+                                ui.label("Logo uprostřed:");
+                                if ui.button("Vybrat logo…").clicked()
+                                    && let Some(p) = FileDialog::new()
+                                        .add_filter("Obrázky", &["png", "jpg", "jpeg", "tif", "tiff"])
+                                        .pick_file()
+                                {
+                                    self.logo_path = Some(p);
+                                    self.bump_preview();
+                                }
+                                if ui.add_enabled(self.logo_path.is_some(), egui::Button::new("Odebrat")).clicked() {
+                                    self.logo_path = None;
+                                    self.bump_preview();
+                                }
+                            });
+                            ui.monospace(format!(
+                                "Logo: {}",
+                                self.logo_path
+                                    .as_deref()
+                                    .map(shorten)
+                                    .unwrap_or_else(|| "<bez loga>".to_string())
+                            ));
+                            ui.add_enabled_ui(self.logo_path.is_some(), |ui| {
+                                if ui
+                                    .add(
+                                        egui::Slider::new(&mut self.logo_size_percent, 10..=30)
+                                            .text("Velikost loga")
+                                            .suffix(" % symbolu")
+                                            .step_by(1.0),
+                                    )
+                                    .changed()
+                                {
+                                    self.bump_preview();
+                                }
+                                if ui
+                                    .checkbox(&mut self.logo_knockout, "Vyčistit moduly pod logem")
+                                    .changed()
+                                {
+                                    self.bump_preview();
+                                }
+                                let min_ec = min_ec_for_logo(self.logo_size_percent, self.logo_knockout);
+                                if self.logo_path.is_some() && min_ec > self.ec_level {
+                                    ui.small(format!(
+                                        "Korekce chyb bude kvůli logu zvýšena na {}.",
+                                        ec_level_label(min_ec)
+                                    ));
+                                }
+                            });
+
+                            ui.separator();
+
+                            // Pozice jen pokud není bulk a máme overlay mód
+                            ui.add_enabled_ui(!self.bulk_mode, |ui| {
+                                ui.label("Pozice (jen pro vložení do obrázku):");
+                                ComboBox::from_id_source("corner")
+                                    .selected_text(match self.corner {
+                                        Corner::Southeast => "pravý-dolní (SE)",
+                                        Corner::Southwest => "levý-dolní (SW)",
+                                        Corner::Northeast => "pravý-horní (NE)",
+                                        Corner::Northwest => "levý-horní (NW)",
+                                        Corner::Custom => "vlastní (X/Y)",
+                                    })
+                                    .show_ui(ui, |ui| {
+                                        let current = self.corner;
+                                        if ui.selectable_label(current == Corner::Southeast, "pravý-dolní (SE)").clicked() { self.corner = Corner::Southeast; self.bump_preview(); }
+                                        if ui.selectable_label(current == Corner::Southwest, "levý-dolní (SW)").clicked() { self.corner = Corner::Southwest; self.bump_preview(); }
+                                        if ui.selectable_label(current == Corner::Northeast, "pravý-horní (NE)").clicked() { self.corner = Corner::Northeast; self.bump_preview(); }
+                                        if ui.selectable_label(current == Corner::Northwest, "levý-horní (NW)").clicked() { self.corner = Corner::Northwest; self.bump_preview(); }
+                                        if ui.selectable_label(current == Corner::Custom, "vlastní (X/Y)").clicked() { self.corner = Corner::Custom; self.bump_preview(); }
+                                    });
+
+                                // Odsazení
+                                let (max_w, max_h) = self.base_dims.unwrap_or((4000, 4000));
+                                let slider_max_dx = max_w as i32;
+                                let slider_max_dy = max_h as i32;
+
+                                match self.corner {
+                                    Corner::Custom => {
+                                        ui.label("Souřadnice (px) od levého-horního rohu:");
+                                        if ui
+                                            .add(
+                                                egui::Slider::new(&mut self.offset_x, 0..=slider_max_dx)
+                                                    .text("X")
+                                                    .suffix(" px")
+                                                    .step_by(1.0),
+                                            )
+                                            .changed()
+                                        {
+                                            self.bump_preview();
+                                        }
+                                        if ui
+                                            .add(
+                                                egui::Slider::new(&mut self.offset_y, 0..=slider_max_dy)
+                                                    .text("Y")
+                                                    .suffix(" px")
+                                                    .step_by(1.0),
+                                            )
+                                            .changed()
+                                        {
+                                            self.bump_preview();
+                                        }
+                                    }
+                                    _ => {
+                                        ui.label("Odsazení od kraje (px):");
+                                        if ui
+                                            .add(
+                                                egui::Slider::new(&mut self.offset_x, 0..=slider_max_dx)
+                                                    .text("dx")
+                                                    .suffix(" px")
+                                                    .step_by(1.0),
+                                            )
+                                            .changed()
+                                        {
+                                            self.bump_preview();
+                                        }
+                                        if ui
+                                            .add(
+                                                egui::Slider::new(&mut self.offset_y, 0..=slider_max_dy)
+                                                    .text("dy")
+                                                    .suffix(" px")
+                                                    .step_by(1.0),
+                                            )
+                                            .changed()
+                                        {
+                                            self.bump_preview();
+                                        }
+                                    }
+                                }
+                            });
+                        });
+
+                        // Akce
+                        ui.horizontal(|ui| {
+                            let green = egui::Color32::from_rgb(16, 163, 74);
+
+                            if !self.bulk_mode {
+                                // Uložit do obrázku
+                                let overlay_btn = egui::Button::new(
+                                    egui::RichText::new("Vložit QR a uložit").color(egui::Color32::WHITE)
+                                )
+                                .fill(green);
+                                let overlay_enabled = self.input_path.is_some();
+                                if ui.add_enabled(overlay_enabled, overlay_btn).clicked() {
+                                    self.start_job(SaveMode::OverlayIntoImage);
+                                }
+
+                                // Uložit jen QR (single)
+                                let qr_btn = egui::Button::new(
+                                    egui::RichText::new("Uložit jen QR").color(egui::Color32::WHITE)
+                                )
+                                .fill(egui::Color32::from_rgb(52, 120, 246));
+                                if ui.add(qr_btn).clicked() {
+                                    self.start_job(SaveMode::QrOnlySingle);
+                                }
+                            } else {
+                                // Hromadné generování QR
+                                let bulk_btn = egui::Button::new(
+                                    egui::RichText::new("Vygenerovat QR (hromadně)").color(egui::Color32::WHITE)
+                                )
+                                .fill(egui::Color32::from_rgb(52, 120, 246));
+                                if ui.add(bulk_btn).clicked() {
+                                    self.start_job(SaveMode::QrOnlyBulk);
+                                }
+                            }
+
+                            if ui.button("Konec").clicked() {
+                                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                            }
+                        });
+
+                        if !self.last_message.is_empty() {
+                            ui.separator();
+                            ui.label(&self.last_message);
+                        }
+                    });
+
+                    if self.is_busy {
+                        ui.separator();
+                        self.progress_ui(ui);
+                    }
+                });
+
+                // === PRAVÝ SLOUPEC – náhled ===
+                cols[1].vertical(|ui| {
+                    ui.group(|ui| {
+                        ui.label(if self.bulk_mode { "Živý náhled (první URL):" } else { "Živý náhled:" });
+                        self.ensure_preview(ctx);
+                        if let Some(err) = &self.preview_error {
+                            ui.colored_label(egui::Color32::RED, err);
+                        }
+                        if let Some(tex) = &self.preview {
+                            let max = Vec2::new(520.0, 520.0);
+                            let size = tex.size_vec2();
+                            let scale = (max.x / size.x).min(max.y / size.y).min(1.0);
+                            let desired = size * scale;
+                            ui.image((tex.id(), desired));
+                        } else {
+                            ui.monospace("— žádný náhled —");
+                        }
+                    });
+                });
+            });
+
+            // === Mapování sloupců tabulky ===
+            self.column_mapping_window(ctx);
+
+            // === Modální okno s výsledkem ===
+            if self.result_modal_open {
+                let mut is_open = true;
+                let mut close_now = false;
+
+                let failed = self.last_report.as_ref().map_or(0, |r| r.failed_count());
+                let cancelled = self.last_report.as_ref().is_some_and(|r| r.cancelled);
+                let title = match (self.last_saved_path.is_some(), failed) {
+                    _ if cancelled => "Přerušeno",
+                    (true, 0) if self.last_unreadable && self.last_report.is_none() => "Nečitelný QR",
+                    (true, 0) if !self.last_unreadable => "Hotovo",
+                    (true, _) => "Hotovo s chybami",
+                    (false, _) => "Chyba",
+                };
+                egui::Window::new(title)
+                    .collapsible(false)
+                    .resizable(false)
+                    .default_size([460.0, 160.0])
+                    .min_size([360.0, 120.0])
+                    .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                    .open(&mut is_open)
+                    .show(ctx, |ui| {
+                        ui.vertical_centered(|ui| {
+                            ui.label(&self.last_message);
+                            if let Some(p) = &self.last_saved_path {
+                                ui.add_space(6.0);
+                                ui.horizontal_centered(|ui| {
+                                    if ui.button("Otevřít výsledek").clicked() {
+                                        let _ = open::that(p);
+                                    }
+                                    if ui.button("Otevřít složku").clicked() {
+                                        #[cfg(target_os = "windows")]
+                                        {
+                                            let _ = std::process::Command::new("explorer")
+                                                .args(["/select,", &p.to_string_lossy()])
+                                                .spawn();
+                                        }
+                                        #[cfg(not(target_os = "windows"))]
+                                        {
+                                            if let Some(parent) = p.parent() {
+                                                let _ = open::that(parent);
+                                            }
+                                        }
+                                    }
+                                });
+                            }
+                            if let Some(report) = &self.last_report {
+                                ui.add_space(6.0);
+                                bulk_report_table(ui, report);
+                                if ui.button("Uložit report (CSV)…").clicked()
+                                    && let Some(p) = FileDialog::new()
+                                        .add_filter("CSV", &["csv"])
+                                        .set_file_name("report.csv")
+                                        .save_file()
+                                    && let Err(e) = report.write_csv(&p)
+                                {
+                                    self.last_message = format!("Chyba: {e}");
+                                }
+                            }
+                            ui.add_space(6.0);
+                            if ui.button("OK").clicked() {
+                                close_now = true;
+                            }
+                        });
+                    });
+
+                self.result_modal_open = is_open && !close_now;
+
+                let painter = ui.painter_at(ui.max_rect());
+                painter.rect_filled(ui.max_rect(), 0.0, egui::Color32::from_black_alpha(120));
+            }
+        });
+    }
+}
+
+fn main() -> eframe::Result<()> {
+    // s podpříkazem (single/bulk/overlay) běží bez GUI
+    if let Some(cmd) = cli::Cli::parse().command {
+        cli::attach_parent_console();
+        if let Err(e) = cli::run(cmd) {
+            eprintln!("Chyba: {e:#}");
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let native_options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_inner_size([980.0, 760.0])
+            .with_min_inner_size([820.0, 560.0])
+            .with_title("Kjů ár")
+            .with_app_id("kju-ar"),
+        ..Default::default()
+    };
+    eframe::run_native(
+        "Kjů ár",
+        native_options,
+        Box::new(|cc| Box::new(AppState::new(cc))),
+    )
+}
+
+/// Pomocné metody stavu
+impl AppState {
+    /// Obnoví uložené nastavení; cesty k už neexistujícím souborům zahodí.
+    fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let mut app: Self = cc
+            .storage
+            .and_then(|s| eframe::get_value(s, eframe::APP_KEY))
+            .unwrap_or_default();
+        app.input_path = app.input_path.filter(|p| p.is_file());
+        app.logo_path = app.logo_path.filter(|p| p.is_file());
+        app.export_dir = app.export_dir.filter(|p| p.is_dir());
+        app.output_path = app
+            .output_path
+            .filter(|p| p.parent().is_none_or(|d| d.as_os_str().is_empty() || d.is_dir()));
+        app.refresh_base_dims();
+        app
+    }
+
+    fn bump_preview(&mut self) {
+        self.preview_key.clear();
+    }
+
+    fn current_preset(&self, name: String) -> presets::Preset {
+        let [mr, mg, mb, _] = self.module_color.to_srgba_unmultiplied();
+        let [br, bg, bb, _] = self.background_color.to_srgba_unmultiplied();
+        presets::Preset {
+            name,
+            size_px: self.qr_size_px,
+            module_rgb: (mr, mg, mb),
+            background_rgb: (br, bg, bb),
+            alpha_percent: self.qr_alpha_percent,
+            rounding_percent: self.rounding_percent,
+            quiet_zone_mod: self.quiet_zone_mod,
+            module_shape: self.module_shape,
+            eyes: self.eye_style(),
+            gradient: self.gradient_enabled.then(|| self.gradient.clone()),
+            print_size_mm: self.pdf_size_mm,
+            dpi: self.print_exact.then_some(self.dpi),
+            cut_background: self.cut_white_background,
+            corner: self.corner,
+            offset_x: self.offset_x,
+            offset_y: self.offset_y,
+        }
+    }
+
+    fn apply_preset(&mut self, p: &presets::Preset) {
+        self.preset_name = p.name.clone();
+        self.qr_size_px = p.size_px.clamp(64, 2048);
+        self.module_color = Color32::from_rgb(p.module_rgb.0, p.module_rgb.1, p.module_rgb.2);
+        self.background_color = Color32::from_rgb(p.background_rgb.0, p.background_rgb.1, p.background_rgb.2);
+        self.qr_alpha_percent = p.alpha_percent.min(100);
+        self.rounding_percent = p.rounding_percent.min(50);
+        self.quiet_zone_mod = p.quiet_zone_mod.min(10);
+        self.pdf_size_mm = p.print_size_mm.clamp(5.0, 1000.0);
+        self.print_exact = p.dpi.is_some();
+        if let Some(dpi) = p.dpi {
+            self.dpi = dpi.clamp(72, 2400);
+        }
+        self.module_shape = p.module_shape;
+        self.gradient_enabled = p.gradient.as_ref().is_some_and(|g| g.stops.len() >= 2);
+        if let Some(g) = &p.gradient
+            && g.stops.len() >= 2
+        {
+            self.gradient = g.clone();
+        }
+        self.eyes_custom = p.eyes.is_some();
+        if let Some(e) = p.eyes {
+            self.eye_frame = e.frame;
+            self.eye_pupil = e.pupil;
+            self.eye_frame_color = Color32::from_rgb(e.frame_rgb.0, e.frame_rgb.1, e.frame_rgb.2);
+            self.eye_pupil_color = Color32::from_rgb(e.pupil_rgb.0, e.pupil_rgb.1, e.pupil_rgb.2);
+        }
+        self.cut_white_background = p.cut_background;
+        self.corner = p.corner;
+        self.offset_x = p.offset_x.max(0);
+        self.offset_y = p.offset_y.max(0);
+        self.bump_preview();
+    }
+
+    /// Fyzická velikost v mm nebo palcích; s přesnou tiskovou velikostí i DPI a výsledný rozměr.
+    fn print_size_ui(&mut self, ui: &mut egui::Ui) {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label(if self.print_exact { "Fyzická velikost:" } else { "Velikost QR v PDF:" });
+            if self.print_inches {
+                let mut inches = self.pdf_size_mm / 25.4;
+                if ui
+                    .add(egui::DragValue::new(&mut inches).clamp_range(0.2..=39.37).speed(0.02).suffix(" in"))
+                    .changed()
+                {
+                    self.pdf_size_mm = inches * 25.4;
+                    changed = true;
+                }
+            } else {
+                changed |= ui
+                    .add(egui::DragValue::new(&mut self.pdf_size_mm).clamp_range(5.0..=1000.0).speed(0.5).suffix(" mm"))
+                    .changed();
+            }
+            ui.selectable_value(&mut self.print_inches, false, "mm");
+            ui.selectable_value(&mut self.print_inches, true, "in");
+        });
+        if self.print_exact {
+            ui.horizontal(|ui| {
+                ui.label("Rozlišení:");
+                changed |= ui.add(egui::DragValue::new(&mut self.dpi).clamp_range(72..=2400).suffix(" dpi")).changed();
+                // skutečný rozměr po zaokrouhlení na celé pixely na modul (podle obsahu)
+                let text = if self.bulk_mode {
+                    self.bulk_items().ok().and_then(|items| Some(items.first()?.tracked_payload(&self.utm())))
+                } else {
+                    self.content().ok()
+                };
+                if let Some(text) = text
+                    && let Ok(v) = qr_version(&text, self.effective_ec())
+                {
+                    let px = self.qr_style().raster_px(17 + 4 * v as u32);
+                    let real = px as f32 / self.dpi as f32;
+                    let real = if self.print_inches { format!("{real:.2} in") } else { format!("{:.1} mm", real * 25.4) };
+                    ui.weak(format!("→ {px} px = {real}"));
+                }
+            });
+        }
+        if changed {
+            self.bump_preview();
+        }
+    }
+
+    /// Lineární nebo kruhový přechod se dvěma a více barvami.
+    fn gradient_ui(&mut self, ui: &mut egui::Ui) {
+        if ui.checkbox(&mut self.gradient_enabled, "Barevný přechod modulů").changed() {
+            self.bump_preview();
+        }
+        if !self.gradient_enabled {
+            return;
+        }
+        let mut changed = false;
+        let linear = matches!(self.gradient.kind, GradientKind::Linear { .. });
+        ui.horizontal(|ui| {
+            if ui.radio(linear, "Lineární").clicked() && !linear {
+                self.gradient.kind = GradientKind::Linear { angle_deg: 0.0 };
+                changed = true;
+            }
+            if ui.radio(!linear, "Kruhový").clicked() && linear {
+                self.gradient.kind = GradientKind::Radial;
+                changed = true;
+            }
+        });
+        if let GradientKind::Linear { angle_deg } = &mut self.gradient.kind {
+            changed |= ui
+                .add(egui::Slider::new(angle_deg, 0.0..=360.0).text("Úhel").suffix("°").step_by(1.0))
+                .changed();
+        }
+
+        // zastávky: barva a poloha; aspoň dvě
+        let can_remove = self.gradient.stops.len() > 2;
+        let mut remove = None;
+        for (i, stop) in self.gradient.stops.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                let mut c = [stop.rgb.0, stop.rgb.1, stop.rgb.2];
+                if egui::color_picker::color_edit_button_srgb(ui, &mut c).changed() {
+                    stop.rgb = (c[0], c[1], c[2]);
+                    changed = true;
+                }
+                let mut pct = stop.offset * 100.0;
+                if ui.add(egui::Slider::new(&mut pct, 0.0..=100.0).suffix(" %").step_by(1.0)).changed() {
+                    stop.offset = pct / 100.0;
+                    changed = true;
+                }
+                if can_remove && ui.small_button("✕").on_hover_text("Odebrat barvu").clicked() {
+                    remove = Some(i);
+                }
+            });
+        }
+        if let Some(i) = remove {
+            self.gradient.stops.remove(i);
+            changed = true;
+        }
+        if ui.button("+ Přidat barvu").clicked() {
+            // uprostřed přechodu v jeho současné barvě – vzhled se nezmění, dokud ji uživatel neupraví
+            let rgb = self.gradient.color_at(0.5);
+            self.gradient.stops.push(GradientStop { offset: 0.5, rgb });
+            changed = true;
+        }
+        if changed {
+            self.bump_preview();
+        }
+    }
+
+    /// Tvar a barva rámečku a středu tří rohových značek.
+    fn eyes_ui(&mut self, ui: &mut egui::Ui) {
+        if ui.checkbox(&mut self.eyes_custom, "Vlastní vzhled rohových značek").changed() {
+            self.bump_preview();
+        }
+        if !self.eyes_custom {
+            return;
+        }
+        let mut changed = false;
+        let rows = [
+            ("Rámeček:", "eye_frame", &mut self.eye_frame, &mut self.eye_frame_color),
+            ("Střed:", "eye_pupil", &mut self.eye_pupil, &mut self.eye_pupil_color),
+        ];
+        for (label, id, shape, color) in rows {
+            ui.horizontal(|ui| {
+                ui.label(label);
+                ComboBox::from_id_source(id).selected_text(eye_shape_label(*shape)).show_ui(ui, |ui| {
+                    for s in EYE_SHAPES {
+                        changed |= ui.selectable_value(shape, s, eye_shape_label(s)).changed();
+                    }
+                });
+                changed |= egui::color_picker::color_edit_button_srgba(ui, color, egui::color_picker::Alpha::Opaque)
+                    .changed();
+            });
+        }
+        if changed {
+            self.bump_preview();
+        }
+    }
+
+    fn eye_style(&self) -> Option<EyeStyle> {
+        let rgb = |c: Color32| {
+            let [r, g, b, _] = c.to_srgba_unmultiplied();
+            (r, g, b)
+        };
+        self.eyes_custom.then(|| EyeStyle {
+            frame: self.eye_frame,
+            pupil: self.eye_pupil,
+            frame_rgb: rgb(self.eye_frame_color),
+            pupil_rgb: rgb(self.eye_pupil_color),
+        })
+    }
+
+    /// UTM parametry – společné pro jednotlivý odkaz i všechny řádky hromadného exportu.
+    fn utm_ui(&mut self, ui: &mut egui::Ui) {
+        if ui.checkbox(&mut self.utm_enabled, "Přidat UTM parametry kampaně").changed() {
+            self.bump_preview();
+        }
+        if !self.utm_enabled {
+            return;
+        }
+        let mut changed = false;
+        egui::Grid::new("utm_form").num_columns(2).show(ui, |ui| {
+            let u = &mut self.utm;
+            let values = [&mut u.source, &mut u.medium, &mut u.campaign, &mut u.term, &mut u.content];
+            for (key, value) in Utm::KEYS.into_iter().zip(values) {
+                ui.label(key);
+                changed |= ui.add(TextEdit::singleline(value).desired_width(f32::INFINITY)).changed();
+                ui.end_row();
+            }
+        });
+        if changed {
+            self.bump_preview();
+        }
+        if self.bulk_mode {
+            ui.weak("Platí pro každý odkaz; sloupce utm_* z tabulky je po řádcích přepíší.");
+        } else if let Ok(url) = self.content() {
+            ui.add(egui::Label::new(egui::RichText::new(url).monospace().weak()).wrap(true));
+        }
+    }
+
+    /// Společné UTM parametry (prázdné, když jsou vypnuté).
+    fn utm(&self) -> Utm {
+        if self.utm_enabled { self.utm.clone() } else { Utm::default() }
+    }
+
+    /// Verze QR pro obsah; nad `DENSE_QR_VERSION` varuje, že kód bude hustý.
+    fn qr_version_hint(&self, ui: &mut egui::Ui, text: &str) {
+        let ec = self.effective_ec();
+        match qr_version(text, ec) {
+            Ok(v) => {
+                let w = 17 + 4 * v as u32;
+                let msg = format!("{} znaků → verze QR {v} ({w}×{w} modulů)", text.chars().count());
+                if v > DENSE_QR_VERSION {
+                    ui.colored_label(
+                        Color32::from_rgb(217, 119, 6),
+                        format!("{msg} – kód bude hustý a hůř čitelný, zkrať údaje nebo sniž korekci."),
+                    );
+                } else {
+                    ui.weak(msg);
+                }
+            }
+            Err(e) => {
+                ui.colored_label(Color32::RED, e.to_string());
+            }
+        }
+    }
+
+    /// Korekce předepsaná typem obsahu (hromadný export typ obsahu nepoužívá).
+    fn required_ec(&self) -> Option<EcLevel> {
+        if self.bulk_mode { None } else { self.payload.required_ec() }
+    }
+
+    /// Úroveň korekce, se kterou se QR opravdu vykreslí (typ obsahu, logo).
+    fn effective_ec(&self) -> EcLevel {
+        let ec = self.required_ec().unwrap_or(self.ec_level);
+        match self.logo_path {
+            Some(_) => ec.max(min_ec_for_logo(self.logo_size_percent, self.logo_knockout)),
+            None => ec,
+        }
+    }
+
+    /// Text pro jednotlivý QR; odmítne logo, které by změnilo předepsanou korekci.
+    fn content(&self) -> anyhow::Result<String> {
+        let text = match self.payload.kind {
+            PayloadKind::Url => self.utm().apply(&self.payload.payload(&self.url)?),
+            _ => self.payload.payload(&self.url)?,
+        };
+        if let Some(ec) = self.required_ec()
+            && self.effective_ec() != ec
+        {
+            anyhow::bail!(
+                "Tento typ obsahu vyžaduje korekci chyb {}, logo by ji zvýšilo – zmenši nebo odeber logo",
+                ec_level_label(ec)
+            );
+        }
+        Ok(text)
+    }
+
+    fn qr_style(&self) -> QrStyle {
+        let [mr, mg, mb, _] = self.module_color.to_srgba_unmultiplied();
+        let background_rgb = if self.cut_white_background {
+            None
+        } else {
+            let [br, bg, bb, _] = self.background_color.to_srgba_unmultiplied();
+            Some((br, bg, bb))
+        };
+        QrStyle {
+            size_px: self.qr_size_px,
+            module_rgb: (mr, mg, mb),
+            gradient: self.gradient_enabled.then(|| self.gradient.clone()),
+            background_rgb,
+            alpha_percent: self.qr_alpha_percent,
+            rounding_percent: self.rounding_percent,
+            module_shape: self.module_shape,
+            eyes: self.eye_style(),
+            ec_level: self.required_ec().unwrap_or(self.ec_level),
+            print_size_mm: self.pdf_size_mm,
+            quiet_zone_mod: self.quiet_zone_mod,
+            dpi: self.print_exact.then_some(self.dpi),
+        }
+    }
+
+    fn refresh_base_dims(&mut self) {
+        self.base_dims = None;
+        if let Some(p) = &self.input_path
+            && let Ok((w, h)) = image::image_dimensions(p)
+        {
+            self.base_dims = Some((w, h));
+        }
+    }
+
+    fn ensure_preview(&mut self, ctx: &egui::Context) {
+        let key = self.preview_signature();
+        if self.preview_key == key {
+            return;
+        }
+        self.preview_key = key.clone();
+
+        match self.render_preview_color_image() {
+            Ok(ci) => {
+                if let Some(tex) = &mut self.preview {
+                    tex.set(ci, TextureOptions::LINEAR);
+                } else {
+                    self.preview = Some(ctx.load_texture("preview", ci, TextureOptions::LINEAR));
+                }
+                self.preview_error = None;
+            }
+            Err(e) => {
+                self.preview = None;
+                self.preview_error = Some(format!("Náhled nelze vytvořit: {e}"));
+            }
+        }
+    }
+
+    fn preview_signature(&self) -> String {
+        let in_tag = if self.bulk_mode {
+            "bulk".to_string()
+        } else {
+            self.input_path
+                .as_deref()
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_else(|| "qr-only".to_string())
+        };
+        let mtime = self
+            .input_path
+            .as_deref()
+            .and_then(|p| std::fs::metadata(p).ok())
+            .and_then(|m| m.modified().ok())
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        let mticks = mtime
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        let logo_tag = self
+            .logo_path
+            .as_deref()
+            .map(|p| {
+                let lt = std::fs::metadata(p)
+                    .and_then(|m| m.modified())
+                    .ok()
+                    .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0);
+                format!("{}@{lt}:{}%:{}", p.to_string_lossy(), self.logo_size_percent, self.logo_knockout)
+            })
+            .unwrap_or_default();
+
+        let [mr, mg, mb, _] = self.module_color.to_srgba_unmultiplied();
+        let [br, bg, bb, _] = self.background_color.to_srgba_unmultiplied();
+
+        format!(
+            "{in}|{mt}|{u}|{table}|{bulk}|{qr}px|ec={ec:?}|{corner:?}|{ox},{oy}|{alpha}%|cut={cut}|mod={mr},{mg},{mb}|bg={br},{bg},{bb}|round={round}|qz={qz}|dpi={dpi:?}@{mm}|shape={shape:?}|grad={grad:?}|eyes={eyes:?}|logo={logo_tag}|cap={cap:?}|utm={utm:?}|fmt={fmt}",
+            in = in_tag,
+            mt = mticks,
+            u = if self.bulk_mode {
+                self.bulk_urls.clone()
+            } else {
+                self.content().unwrap_or_else(|e| format!("!{e}"))
+            },
+            table = self
+                .table_import
+                .as_ref()
+                .filter(|t| self.bulk_mode && t.confirmed)
+                .map(|t| format!("{}:{:?}", t.path.display(), t.mapping))
+                .unwrap_or_default(),
+            bulk = self.bulk_mode,
+            qr = self.qr_size_px,
+            ec = self.ec_level,
+            corner = self.corner,
+            ox = self.offset_x,
+            oy = self.offset_y,
+            alpha = self.qr_alpha_percent,
+            cut = self.cut_white_background,
+            round = self.rounding_percent,
+            qz = self.quiet_zone_mod,
+            dpi = self.print_exact.then_some(self.dpi),
+            mm = self.pdf_size_mm,
+            shape = self.module_shape,
+            grad = self.gradient_enabled.then_some(&self.gradient),
+            eyes = self.eye_style(),
+            cap = self.payload.caption(),
+            utm = self.utm(),
+            fmt = self.out_format.ext(),
+        )
+    }
+
+    /// Položky hromadného exportu – z potvrzené tabulky, jinak z URL po řádcích.
+    fn bulk_items(&self) -> kju_ar::Result<Vec<BulkItem>> {
+        match &self.table_import {
+            Some(t) if t.confirmed => t.mapping.items(&t.table),
+            _ => Ok(parse_url_lines(&self.bulk_urls).into_iter().map(BulkItem::new).collect()),
+        }
+    }
+
+    /// Okno pro přiřazení sloupců tabulky (zobrazí se po načtení CSV/TSV).
+    fn column_mapping_window(&mut self, ctx: &egui::Context) {
+        let Some(t) = self.table_import.as_mut().filter(|t| !t.confirmed) else {
+            return;
+        };
+        let mut apply = false;
+        let mut cancel = false;
+
+        egui::Window::new("Mapování sloupců")
+            .collapsible(false)
+            .resizable(true)
+            .default_width(560.0)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.checkbox(&mut t.mapping.has_header, "První řádek je záhlaví");
+                let headers = t.table.headers(t.mapping.has_header);
+
+                egui::Grid::new("mapping").num_columns(2).show(ui, |ui| {
+                    ui.label("Obsah QR (URL):");
+                    ComboBox::from_id_source("map_payload")
+                        .selected_text(headers.get(t.mapping.payload).map(String::as_str).unwrap_or("—"))
+                        .show_ui(ui, |ui| {
+                            for (i, h) in headers.iter().enumerate() {
+                                ui.selectable_value(&mut t.mapping.payload, i, h);
+                            }
+                        });
+                    ui.end_row();
+
+                    let [utm_source, utm_medium, utm_campaign, utm_term, utm_content] = &mut t.mapping.utm;
+                    for (label, slot) in [
+                        ("Název souboru:", &mut t.mapping.file_name),
+                        ("Barva modulů:", &mut t.mapping.module_color),
+                        ("Pozadí:", &mut t.mapping.background_color),
+                        ("Velikost (px):", &mut t.mapping.size),
+                        ("Popisek:", &mut t.mapping.caption),
+                        ("utm_source:", utm_source),
+                        ("utm_medium:", utm_medium),
+                        ("utm_campaign:", utm_campaign),
+                        ("utm_term:", utm_term),
+                        ("utm_content:", utm_content),
+                    ] {
+                        ui.label(label);
+                        ComboBox::from_id_source(label)
+                            .selected_text(slot.and_then(|i| headers.get(i)).map(String::as_str).unwrap_or("— nepoužít —"))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(slot, None, "— nepoužít —");
+                                for (i, h) in headers.iter().enumerate() {
+                                    ui.selectable_value(slot, Some(i), h);
+                                }
+                            });
+                        ui.end_row();
+                    }
+                });
+
+                ui.separator();
+                ui.label("Ukázka dat:");
+                let skip = usize::from(t.mapping.has_header);
+                egui::ScrollArea::both().max_height(160.0).show(ui, |ui| {
+                    egui::Grid::new("mapping_preview").striped(true).show(ui, |ui| {
+                        for h in &headers {
+                            ui.strong(h);
+                        }
+                        ui.end_row();
+                        for row in t.table.rows.iter().skip(skip).take(5) {
+                            for c in 0..headers.len() {
+                                ui.monospace(row.get(c).map(String::as_str).unwrap_or(""));
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+                ui.small(format!("Řádků s daty: {}", t.table.rows.len().saturating_sub(skip)));
+
+                if let Some(err) = &t.error {
+                    ui.colored_label(egui::Color32::RED, err);
+                }
+                ui.horizontal(|ui| {
+                    if ui.button("Použít").clicked() {
+                        apply = true;
+                    }
+                    if ui.button("Zrušit").clicked() {
+                        cancel = true;
+                    }
+                });
+            });
+
+        if apply {
+            match t.mapping.items(&t.table) {
+                Ok(_) => {
+                    t.confirmed = true;
+                    t.error = None;
+                }
+                Err(e) => t.error = Some(e.to_string()),
+            }
+            self.bump_preview();
+        }
+        if cancel {
+            self.table_import = None;
+            self.bump_preview();
+        }
+    }
+
+    /// Náhled:
+    /// - bulk: zobrazí QR první položky (URL nebo řádek tabulky)
+    /// - single: pokud je vstupní obrázek, ukáže overlay; jinak ukáže samostatný QR
+    fn render_preview_color_image(&self) -> anyhow::Result<ColorImage> {
+        use anyhow::{anyhow, Context};
+
+        let style = self.qr_style();
+        let logo = load_logo(self.logo_path.as_deref(), self.logo_size_percent, self.logo_knockout)?;
+        // tisková velikost: náhled v odvozeném rozměru, samostatný QR nejvýš 1024 px
+        let preview_style = |text: &str, s: &QrStyle| -> kju_ar::Result<QrStyle> {
+            Ok(match s.dpi {
+                Some(_) => QrStyle { size_px: raster_size_px(text, s, logo.as_ref())?.min(1024), dpi: None, ..s.clone() },
+                None => s.clone(),
+            })
+        };
+
+        if self.bulk_mode {
+            // V bulk režimu vždy ukazujeme samostatný QR (podle první položky)
+            let items = self.bulk_items()?;
+            let item = items.first().ok_or_else(|| anyhow!("Vlož aspoň jednu URL (po řádku)"))?;
+            let payload = item.tracked_payload(&self.utm());
+            let item_style = preview_style(&payload, &item.style(&style))?;
+            let mut qr_img = build_qr_image(&payload, &item_style, logo.as_ref())?;
+            if let Some(c) = &item.caption {
+                qr_img = add_caption(&qr_img, Caption::Text(c), &item_style)?;
+            }
+            let [w, h] = [qr_img.width() as usize, qr_img.height() as usize];
+            return Ok(ColorImage::from_rgba_unmultiplied([w, h], qr_img.as_raw()));
+        }
+
+        // zdrojový text pro náhled (URL nebo sestavený obsah formuláře)
+        let preview_url = self.content()?;
+
+        if self.input_path.is_none() {
+            // Samostatný QR náhled (single)
+            let style = preview_style(&preview_url, &style)?;
+            let mut qr_img = build_qr_image(&preview_url, &style, logo.as_ref())?;
+            if let Some(c) = self.payload.caption() {
+                qr_img = add_caption(&qr_img, c, &style)?;
+            }
+            let [w, h] = [qr_img.width() as usize, qr_img.height() as usize];
+            return Ok(ColorImage::from_rgba_unmultiplied([w, h], qr_img.as_raw()));
+        }
+
+        // Overlay náhled (single + máme obrázek)
+        let in_path = self.input_path.as_ref().unwrap();
+        let base = image::open(in_path)
+            .with_context(|| format!("Nejde otevřít obrázek: {}", in_path.display()))?
+            .to_rgba8();
+
+        let (bw, bh) = base.dimensions();
+        let max_w: u32 = 1200;
+        let max_h: u32 = 1200;
+        let scale = (max_w as f32 / bw as f32)
+            .min(max_h as f32 / bh as f32)
+            .min(1.0);
+
+        let disp_w = ((bw as f32 * scale).round() as u32).max(1);
+        let disp_h = ((bh as f32 * scale).round() as u32).max(1);
+
+        let mut base_small =
+            imageops::resize(&base, disp_w, disp_h, imageops::FilterType::Triangle);
+
+        let qr_px = raster_size_px(&preview_url, &style, logo.as_ref())?;
+        let qr_size_scaled = ((qr_px as f32 * scale).round() as u32).clamp(1, 4096);
+        let scaled_style = QrStyle { size_px: qr_size_scaled, dpi: None, ..style };
+        let qr_img = build_qr_image(&preview_url, &scaled_style, logo.as_ref())?;
+
+        let dx = ((self.offset_x.max(0) as f32 * scale).round() as u32).min(disp_w - 1);
+        let dy = ((self.offset_y.max(0) as f32 * scale).round() as u32).min(disp_h - 1);
+        let spec = OverlaySpec { corner: self.corner, offset_x: dx as i32, offset_y: dy as i32 };
+        let margin = overlay_margin_px(&preview_url, &scaled_style, logo.as_ref())?;
+        let (x, y) = spec.position((disp_w, disp_h), qr_img.dimensions(), margin);
+
+        imageops::overlay(&mut base_small, &qr_img, x, y);
+
+        let [w, h] = [base_small.width() as usize, base_small.height() as usize];
+        Ok(ColorImage::from_rgba_unmultiplied([w, h], base_small.as_raw()))
+    }
+
+    fn start_job(&mut self, mode: SaveMode) {
+        use anyhow::Context;
+
+        if self.is_busy {
+            return;
+        }
+
+        // společné parametry
+        let prepared = match mode {
+            SaveMode::QrOnlyBulk => self.bulk_items().map(|items| (String::new(), items)).map_err(anyhow::Error::from),
+            _ => self.content().map(|url| (url, Vec::new())),
+        };
+        let (url, bulk_items) = match prepared {
+            Ok(p) => p,
+            Err(e) => {
+                self.last_saved_path = None;
+                self.last_message = format!("Chyba: {e:#}");
+                self.result_modal_open = true;
+                return;
+            }
+        };
+        let in_path = self.input_path.clone();
+        let out_path = self.output_path.clone();
+        let export_dir = self.export_dir.clone();
+        let options = BulkOptions { workers: self.bulk_workers, verify: self.verify_output, utm: self.utm() };
+        let out_format = self.out_format;
+        let caption = self.payload.caption();
+
+        let style = self.qr_style();
+        let spec = OverlaySpec { corner: self.corner, offset_x: self.offset_x, offset_y: self.offset_y };
+
+        let logo_path = self.logo_path.clone();
+        let logo_size = self.logo_size_percent;
+        let logo_knockout = self.logo_knockout;
+
+        let (tx, rx) = channel::<JobMsg>();
+        self.job_rx = Some(rx);
+        self.is_busy = true;
+        self.job_cancel = Arc::default();
+        self.job_started = Some(Instant::now());
+        self.job_progress = None;
+        let cancel = self.job_cancel.clone();
+
+        std::thread::spawn(move || {
+            let res = (|| -> anyhow::Result<JobResult> {
+                let logo = load_logo(logo_path.as_deref(), logo_size, logo_knockout)?;
+                match mode {
+                    SaveMode::OverlayIntoImage => {
+                        let url = url.trim();
+                        if url.is_empty() {
+                            anyhow::bail!("URL je prázdná");
+                        }
+                        let in_path = in_path.as_ref().context("Není vybrán zdrojový obrázek")?;
+                        let outp = if let Some(p) = &out_path { p.clone() } else { default_out_path(Some(in_path)) };
+                        overlay_into_image(url, &style, logo.as_ref(), in_path, spec, &outp)?;
+                        if options.verify
+                            && let Err(e) = verify_overlay(url, in_path, &outp)
+                        {
+                            return Ok(JobResult::Unreadable(outp, e.describe()));
+                        }
+                        Ok(JobResult::Ok(outp))
+                    }
+                    SaveMode::QrOnlySingle => {
+                        let url = url.trim();
+                        if url.is_empty() {
+                            anyhow::bail!("URL je prázdná");
+                        }
+                        let outp = if let Some(p) = &out_path { p.clone() } else { default_qr_out_path() };
+                        // formát podle přípony zvoleného souboru, jinak podle volby
+                        let fmt = OutputFormat::from_path(&outp).unwrap_or(out_format);
+                        save_qr(url, &style, logo.as_ref(), caption, &outp, fmt)?;
+                        if options.verify
+                            && let Err(e) = verify_qr_file(url, &outp, fmt)
+                        {
+                            return Ok(JobResult::Unreadable(outp, e.describe()));
+                        }
+                        Ok(JobResult::Ok(outp))
+                    }
+                    SaveMode::QrOnlyBulk => {
+                        let dir = export_dir.unwrap_or_else(default_bulk_dir);
+                        let report =
+                            export_bulk_with_progress(&bulk_items, &dir, &style, logo.as_ref(), out_format, options, |p| {
+                                let _ = tx.send(JobMsg::Progress(p.clone()));
+                                !cancel.load(Ordering::Relaxed)
+                            })?;
+                        println!("Hotovo: {} souborů do {}", report.ok_count(), dir.display());
+                        Ok(JobResult::Bulk(report))
+                    }
+                }
+            })();
+
+            let _ = tx.send(JobMsg::Done(res.unwrap_or_else(|e| JobResult::Err(format!("{e:#}")))));
+        });
+    }
+
+    /// Průběh běžícího jobu: u hromadného exportu n/celkem, aktuální položka, odhad zbývajícího času a Zrušit.
+    fn progress_ui(&self, ui: &mut egui::Ui) {
+        let Some(p) = &self.job_progress else {
+            ui.horizontal(|ui| {
+                ui.add(egui::Spinner::new());
+                ui.strong("Zpracovávám…");
+            });
+            return;
+        };
+        let cancelling = self.job_cancel.load(Ordering::Relaxed);
+        ui.add(
+            egui::ProgressBar::new(p.done as f32 / p.total.max(1) as f32)
+                .text(format!("{} / {}", p.done, p.total))
+                .animate(true),
+        );
+        ui.add(egui::Label::new(format!("Zpracovávám: {}", p.payload)).truncate(true));
+        ui.horizontal(|ui| {
+            if let Some(started) = self.job_started
+                && p.done > 0
+            {
+                let per_item = started.elapsed().as_secs_f64() / p.done as f64;
+                let eta = Duration::from_secs_f64(per_item * (p.total - p.done) as f64);
+                ui.label(format!("Zbývá asi {}", format_eta(eta)));
+            }
+            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                if cancelling {
+                    ui.label("Ruším…");
+                } else if ui.button("Zrušit").clicked() {
+                    self.job_cancel.store(true, Ordering::Relaxed);
+                }
+            });
+        });
+    }
+}
+
+fn format_eta(d: Duration) -> String {
+    let s = d.as_secs();
+    if s >= 3600 {
+        format!("{} h {} min", s / 3600, s % 3600 / 60)
+    } else if s >= 60 {
+        format!("{} min {} s", s / 60, s % 60)
+    } else {
+        format!("{s} s")
+    }
+}
+
+/// Tabulka výsledků hromadného exportu (pořadí, obsah, výsledek, důvod).
+fn bulk_report_table(ui: &mut egui::Ui, report: &BulkReport) {
+    egui::ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+        egui::Grid::new("bulk_report").striped(true).num_columns(4).show(ui, |ui| {
+            ui.strong("#");
+            ui.strong("Obsah");
+            ui.strong("Výsledek");
+            ui.strong("Důvod");
+            ui.end_row();
+            for o in &report.outcomes {
+                ui.label(o.index1.to_string());
+                ui.add(egui::Label::new(&o.payload).truncate(true));
+                match (&o.error, &o.unreadable) {
+                    (Some(_), _) => ui.colored_label(egui::Color32::RED, "chyba"),
+                    (None, Some(_)) => ui.colored_label(egui::Color32::from_rgb(217, 119, 6), "nečitelný"),
+                    (None, None) => ui.colored_label(egui::Color32::from_rgb(16, 163, 74), "OK"),
+                };
+                ui.label(o.error.as_deref().or(o.unreadable.as_deref()).unwrap_or(""));
+                ui.end_row();
+            }
+        });
+    });
+}
+
+fn format_label(f: OutputFormat) -> &'static str {
+    match f {
+        OutputFormat::Png => "PNG (.png)",
+        OutputFormat::Jpeg => "JPEG (.jpg)",
+        OutputFormat::Tiff => "TIFF (.tif)",
+        OutputFormat::Svg => "SVG – vektor (.svg)",
+        OutputFormat::Pdf => "PDF – vektor (.pdf)",
+    }
+}
+
+const MODULE_SHAPES: [ModuleShape; 6] = [
+    ModuleShape::Square,
+    ModuleShape::Dot,
+    ModuleShape::Diamond,
+    ModuleShape::VerticalBars,
+    ModuleShape::HorizontalBars,
+    ModuleShape::Liquid,
+];
+
+fn module_shape_label(shape: ModuleShape) -> &'static str {
+    match shape {
+        ModuleShape::Square => "Čtverce",
+        ModuleShape::Dot => "Tečky",
+        ModuleShape::Diamond => "Kosočtverce",
+        ModuleShape::VerticalBars => "Svislé pruhy",
+        ModuleShape::HorizontalBars => "Vodorovné pruhy",
+        ModuleShape::Liquid => "Splývající (liquid)",
+    }
+}
+
+const EYE_SHAPES: [EyeShape; 4] = [EyeShape::Square, EyeShape::Rounded, EyeShape::Circle, EyeShape::Leaf];
+
+fn eye_shape_label(shape: EyeShape) -> &'static str {
+    match shape {
+        EyeShape::Square => "Čtverec",
+        EyeShape::Rounded => "Zaoblený",
+        EyeShape::Circle => "Kruh",
+        EyeShape::Leaf => "List",
+    }
+}
+
+fn ec_level_label(ec: EcLevel) -> &'static str {
+    match ec {
+        EcLevel::L => "L – nízká (~7 %)",
+        EcLevel::M => "M – střední (~15 %)",
+        EcLevel::Q => "Q – vyšší (~25 %)",
+        EcLevel::H => "H – vysoká (~30 %)",
+    }
+}
+
+fn shorten(p: &Path) -> String {
+    let cwd = std::env::current_dir().ok();
+    if let Some(cwd) = cwd
+        && let Some(rel) = pathdiff::diff_paths(p, cwd)
+    {
+        return rel.to_string_lossy().to_string();
+    }
+    p.to_string_lossy().to_string()
+}
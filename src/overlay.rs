@@ -0,0 +1,181 @@
+//! Vložení QR kódu do existujícího obrázku.
+
+use crate::error::{Error, Result};
+use crate::export::save_image_rgba;
+use crate::render::{build_qr_image, qr_matrix};
+use crate::style::{Logo, QrStyle};
+use image::{imageops, DynamicImage, RgbaImage};
+use std::path::Path;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+pub enum Corner {
+    Southeast,
+    Southwest,
+    Northeast,
+    Northwest,
+    Custom, // X/Y od levého-horního
+}
+
+/// Umístění QR ve zdrojovém obrázku.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct OverlaySpec {
+    pub corner: Corner,
+    pub offset_x: i32, // odsazení od kraje, u `Custom` souřadnice X
+    pub offset_y: i32, // odsazení od kraje, u `Custom` souřadnice Y
+}
+
+impl Default for OverlaySpec {
+    fn default() -> Self {
+        Self { corner: Corner::Southeast, offset_x: 10, offset_y: 10 }
+    }
+}
+
+impl OverlaySpec {
+    /// Levý-horní roh obrázku QR o rozměrech `qr` v obrázku o rozměrech `base`.
+    /// Odsazení se měří od viditelného okraje QR: průhledný okraj `margin` px
+    /// (viz [`overlay_margin_px`]) smí přesahovat přes okraj obrázku, symbol nepřeteče.
+    pub fn position(&self, base: (u32, u32), qr: (u32, u32), margin: u32) -> (i64, i64) {
+        let (bw, bh) = (base.0 as i64, base.1 as i64);
+        let (qw, qh) = (qr.0 as i64, qr.1 as i64);
+        let m = margin as i64;
+        let ox = self.offset_x.max(0) as i64;
+        let oy = self.offset_y.max(0) as i64;
+        let (x, y) = match self.corner {
+            Corner::Northwest | Corner::Custom => (ox - m, oy - m),
+            Corner::Northeast => (bw - qw - ox + m, oy - m),
+            Corner::Southwest => (ox - m, bh - qh - oy + m),
+            Corner::Southeast => (bw - qw - ox + m, bh - qh - oy + m),
+        };
+        let fit = |v: i64, b: i64, q: i64| v.min(b - q + m).max(-m);
+        (fit(x, bw, qw), fit(y, bh, qh))
+    }
+}
+
+/// Šířka průhledného okraje (tiché zóny) vykresleného QR v px; s plným pozadím 0,
+/// protože pak je tichá zóna vidět a patří k QR.
+pub fn overlay_margin_px(url: &str, style: &QrStyle, logo: Option<&Logo>) -> Result<u32> {
+    if style.background_rgb.is_some() {
+        return Ok(0);
+    }
+    let qz = style.quiet_zone();
+    let width = qr_matrix(url, style.ec_level, logo)?.width;
+    Ok(style.raster_px(width) * qz / (width + 2 * qz))
+}
+
+/// Vloží QR do zdrojového obrázku a výsledek uloží do `outp` (formát podle přípony).
+pub fn overlay_into_image(
+    url: &str,
+    style: &QrStyle,
+    logo: Option<&Logo>,
+    in_path: &Path,
+    spec: OverlaySpec,
+    outp: &Path,
+) -> Result<()> {
+    let base = compose_overlay(url, style, logo, in_path, spec)?;
+
+    // v PDF bude QR mít zvolenou fyzickou velikost, obrázek se škáluje stejně
+    let mm_per_px = match style.dpi {
+        Some(dpi) => 25.4 / dpi.max(1) as f32,
+        None => style.print_size_mm / style.size_px.max(1) as f32,
+    };
+    save_image_rgba(&DynamicImage::ImageRgba8(base), outp, mm_per_px, style.dpi)
+}
+
+/// Zdrojový obrázek s vloženým QR (bez uložení).
+pub(crate) fn compose_overlay(
+    url: &str,
+    style: &QrStyle,
+    logo: Option<&Logo>,
+    in_path: &Path,
+    spec: OverlaySpec,
+) -> Result<RgbaImage> {
+    let mut base = image::open(in_path)
+        .map_err(|source| Error::OpenImage { path: in_path.to_path_buf(), source })?
+        .to_rgba8();
+
+    let qr_img = build_qr_image(url, style, logo)?;
+    let margin = overlay_margin_px(url, style, logo)?;
+    let (x, y) = spec.position(base.dimensions(), qr_img.dimensions(), margin);
+    imageops::overlay(&mut base, &qr_img, x, y);
+    Ok(base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: (u32, u32) = (1000, 800);
+    const QR: (u32, u32) = (200, 200);
+
+    fn spec(corner: Corner, offset_x: i32, offset_y: i32) -> OverlaySpec {
+        OverlaySpec { corner, offset_x, offset_y }
+    }
+
+    #[test]
+    fn corners_with_margin() {
+        // průhledný okraj 20 px smí přesahovat, viditelný QR je 10 px od kraje
+        for (corner, pos) in [
+            (Corner::Northwest, (-10, -10)),
+            (Corner::Northeast, (810, -10)),
+            (Corner::Southwest, (-10, 610)),
+            (Corner::Southeast, (810, 610)),
+        ] {
+            assert_eq!(spec(corner, 10, 10).position(BASE, QR, 20), pos, "{corner:?}");
+        }
+        // bez okraje přímo od kraje obrázku
+        assert_eq!(spec(Corner::Southeast, 10, 10).position(BASE, QR, 0), (790, 590));
+        assert_eq!(spec(Corner::Northwest, 0, 0).position(BASE, QR, 0), (0, 0));
+        // záporné odsazení se bere jako 0
+        assert_eq!(spec(Corner::Northeast, -50, -5).position(BASE, QR, 20), (820, -20));
+    }
+
+    #[test]
+    fn custom_offsets() {
+        assert_eq!(spec(Corner::Custom, 300, 150).position(BASE, QR, 0), (300, 150));
+        assert_eq!(spec(Corner::Custom, 300, 150).position(BASE, QR, 20), (280, 130));
+        // za okrajem se symbol zarazí o kraj obrázku, přesahuje jen průhledný okraj
+        assert_eq!(spec(Corner::Custom, 950, 790).position(BASE, QR, 20), (820, 620));
+        assert_eq!(spec(Corner::Custom, 950, 790).position(BASE, QR, 0), (800, 600));
+    }
+
+    #[test]
+    fn qr_larger_than_image() {
+        // nevejde se → zarovná se vlevo nahoru, přes okraj přečnívá vpravo a dole
+        for corner in [Corner::Northwest, Corner::Northeast, Corner::Southwest, Corner::Southeast, Corner::Custom] {
+            assert_eq!(spec(corner, 10, 10).position((100, 100), (300, 300), 20), (-20, -20), "{corner:?}");
+            assert_eq!(spec(corner, 10, 10).position((100, 100), (300, 300), 0), (0, 0), "{corner:?}");
+        }
+        // větší jen na šířku
+        assert_eq!(spec(Corner::Southeast, 10, 10).position((100, 800), (300, 300), 0), (0, 490));
+    }
+
+    #[test]
+    fn margin_is_quiet_zone_only_when_transparent() {
+        let url = "https://example.com";
+        let width = qr_matrix(url, QrStyle::default().ec_level, None).unwrap().width;
+        for qz in [0, 2, 4] {
+            let style = QrStyle { dpi: Some(300), print_size_mm: 25.0, quiet_zone_mod: qz, ..QrStyle::default() };
+            let module_px = style.raster_px(width) / (width + 2 * qz);
+            let transparent = QrStyle { background_rgb: None, ..style.clone() };
+            assert_eq!(overlay_margin_px(url, &transparent, None).unwrap(), qz * module_px, "qz {qz}");
+            let solid = QrStyle { background_rgb: Some((255, 255, 255)), ..style };
+            assert_eq!(overlay_margin_px(url, &solid, None).unwrap(), 0, "qz {qz}");
+        }
+    }
+
+    #[test]
+    fn overlay_keeps_dpi() {
+        let dir = tempfile::tempdir().unwrap();
+        let photo = dir.path().join("foto.png");
+        RgbaImage::from_pixel(400, 300, image::Rgba([200, 220, 240, 255])).save(&photo).unwrap();
+        let style = QrStyle { size_px: 120, dpi: Some(300), ..QrStyle::default() };
+        let out = dir.path().join("out.png");
+        overlay_into_image("https://example.com", &style, None, &photo, spec(Corner::Southeast, 10, 10), &out).unwrap();
+
+        let reader = png::Decoder::new(std::fs::File::open(&out).unwrap()).read_info().unwrap();
+        let dims = reader.info().pixel_dims.expect("chybí pHYs");
+        assert_eq!((dims.xppu, dims.unit), (11811, png::Unit::Meter)); // 300 dpi
+    }
+}
@@ -0,0 +1,1117 @@
+//! Strukturovaný obsah QR kódu – z formuláře se sestaví text podle zavedeného formátu.
+
+use crate::error::{Error, Result};
+use jiff::tz::TimeZone;
+use jiff::{civil, Timestamp};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use qrcode::EcLevel;
+use sha1::{Digest, Sha1};
+use std::time::SystemTime;
+
+/// Znaky, které v URI zůstávají bez kódování (RFC 3986 „unreserved“).
+pub(crate) const URI_VALUE: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'.').remove(b'_').remove(b'~');
+/// Adresa v `mailto:` – navíc `@` a `+` (RFC 6068).
+const MAILTO_ADDR: &AsciiSet = &URI_VALUE.remove(b'@').remove(b'+');
+
+/// E-mail (`mailto:adresa?subject=…&body=…`); pole jsou procentově kódovaná.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct EmailPayload {
+    pub to: String, // víc adres odděl čárkou nebo středníkem
+    pub subject: String,
+    pub body: String,
+}
+
+impl EmailPayload {
+    pub fn payload(&self) -> Result<String> {
+        let to = self.to.split([',', ';', '\n']).map(str::trim).filter(|a| !a.is_empty()).collect::<Vec<_>>();
+        if to.is_empty() {
+            return Err(Error::Payload("chybí adresa příjemce".into()));
+        }
+        if let Some(bad) = to.iter().find(|a| !a.contains('@') || a.contains(char::is_whitespace)) {
+            return Err(Error::Payload(format!("„{bad}“ není e-mailová adresa")));
+        }
+        let to = to.iter().map(|a| utf8_percent_encode(a, MAILTO_ADDR).to_string()).collect::<Vec<_>>();
+        let mut out = format!("mailto:{}", to.join(","));
+        // konce řádků v těle jsou podle RFC 6068 CRLF
+        let body = self.body.trim().replace("\r\n", "\n").replace('\n', "\r\n");
+        let query = [("subject", self.subject.trim()), ("body", &body)]
+            .into_iter()
+            .filter(|(_, v)| !v.is_empty())
+            .map(|(k, v)| format!("{k}={}", utf8_percent_encode(v, URI_VALUE)))
+            .collect::<Vec<_>>();
+        if !query.is_empty() {
+            out.push('?');
+            out.push_str(&query.join("&"));
+        }
+        Ok(out)
+    }
+}
+
+/// Zápis SMS.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+pub enum SmsFormat {
+    #[default]
+    Smsto, // `SMSTO:číslo:text` – zavedený formát čteček QR
+    Uri,   // `sms:číslo?body=…` podle RFC 5724
+}
+
+/// SMS na zadané číslo s předvyplněným textem.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct SmsPayload {
+    pub format: SmsFormat,
+    pub number: String,
+    pub message: String,
+}
+
+impl SmsPayload {
+    pub fn payload(&self) -> Result<String> {
+        let number = normalize_phone(&self.number)?;
+        let message = self.message.trim();
+        Ok(match self.format {
+            // SMSTO nemá escapování – text je vše za druhou dvojtečkou
+            SmsFormat::Smsto => format!("SMSTO:{number}:{message}"),
+            SmsFormat::Uri if message.is_empty() => format!("sms:{number}"),
+            SmsFormat::Uri => format!("sms:{number}?body={}", utf8_percent_encode(message, URI_VALUE)),
+        })
+    }
+}
+
+/// Telefonní číslo (`tel:+420…`).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct PhonePayload {
+    pub number: String,
+}
+
+impl PhonePayload {
+    pub fn payload(&self) -> Result<String> {
+        Ok(format!("tel:{}", normalize_phone(&self.number)?))
+    }
+}
+
+/// Poloha na mapě (`geo:šířka,délka`, WGS 84 ve stupních).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct GeoPayload {
+    pub latitude: String, // desetinná tečka i čárka
+    pub longitude: String,
+}
+
+impl GeoPayload {
+    pub fn payload(&self) -> Result<String> {
+        let lat = parse_degrees(&self.latitude, "zeměpisná šířka", 90.0)?;
+        let lon = parse_degrees(&self.longitude, "zeměpisná délka", 180.0)?;
+        Ok(format!("geo:{lat},{lon}"))
+    }
+}
+
+/// Číslo bez mezer, pomlček a závorek; jen číslice s nepovinným `+` na začátku.
+fn normalize_phone(s: &str) -> Result<String> {
+    let number: String = s.chars().filter(|c| !c.is_whitespace() && !matches!(c, '-' | '(' | ')' | '.' | '/')).collect();
+    if number.is_empty() {
+        return Err(Error::Payload("chybí telefonní číslo".into()));
+    }
+    let digits = number.strip_prefix('+').unwrap_or(&number);
+    if !(3..=15).contains(&digits.len()) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(Error::Payload(format!("„{}“ není telefonní číslo", s.trim())));
+    }
+    Ok(number)
+}
+
+/// Stupně jako text pro `geo:` (čárka → tečka); ověří rozsah ±`max`.
+fn parse_degrees(s: &str, label: &str, max: f64) -> Result<String> {
+    let v = s.trim().replace(',', ".");
+    let v = v.strip_prefix('+').unwrap_or(&v);
+    if v.is_empty() {
+        return Err(Error::Payload(format!("chybí {label}")));
+    }
+    match v.parse::<f64>() {
+        Ok(x) if x.is_finite() && x.abs() <= max && !v.contains(['e', 'E']) => Ok(v.to_string()),
+        _ => Err(Error::Payload(format!("{label} „{}“ musí být číslo od −{max} do {max}", s.trim()))),
+    }
+}
+
+/// Zabezpečení Wi-Fi sítě.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+pub enum WifiSecurity {
+    #[default]
+    Wpa, // WPA/WPA2/WPA3 s heslem
+    Wep,
+    None, // otevřená síť
+}
+
+/// Přihlášení k Wi-Fi (`WIFI:T:WPA;S:…;P:…;;`), jak ho čtou fotoaparáty Androidu i iOS.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct WifiPayload {
+    pub ssid: String,
+    pub password: String,
+    pub security: WifiSecurity,
+    pub hidden: bool, // síť nevysílá SSID
+}
+
+impl WifiPayload {
+    pub fn payload(&self) -> Result<String> {
+        if self.ssid.is_empty() {
+            return Err(Error::Payload("chybí název sítě (SSID)".into()));
+        }
+        let kind = match self.security {
+            WifiSecurity::Wpa => "WPA",
+            WifiSecurity::Wep => "WEP",
+            WifiSecurity::None => "nopass",
+        };
+        let mut out = format!("WIFI:T:{kind};S:{};", escape_mecard(&self.ssid));
+        if self.security != WifiSecurity::None {
+            if self.password.is_empty() {
+                return Err(Error::Payload("chybí heslo (nebo zvol otevřenou síť)".into()));
+            }
+            out.push_str(&format!("P:{};", escape_mecard(&self.password)));
+        }
+        if self.hidden {
+            out.push_str("H:true;");
+        }
+        out.push(';');
+        Ok(out)
+    }
+}
+
+/// Escapování hodnot ve formátu MECARD/WIFI (`\ ; , : "` → zpětné lomítko).
+fn escape_mecard(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for ch in s.chars() {
+        if matches!(ch, '\\' | ';' | ',' | ':' | '"') {
+            out.push('\\');
+        }
+        out.push(ch);
+    }
+    out
+}
+
+/// Zápis kontaktu.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+pub enum ContactFormat {
+    #[default]
+    VCard3,
+    VCard4,
+    MeCard, // kratší, ale bez adresy po částech a typů telefonů
+}
+
+/// Kontakt (vizitka) jako vCard 3.0/4.0 nebo MeCard.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct ContactPayload {
+    pub format: ContactFormat,
+    pub first_name: String,
+    pub last_name: String,
+    pub organization: String,
+    pub phones: Vec<String>,
+    pub emails: Vec<String>,
+    pub street: String,
+    pub city: String,
+    pub postal_code: String,
+    pub country: String,
+    pub url: String,
+}
+
+impl ContactPayload {
+    pub fn payload(&self) -> Result<String> {
+        let full_name = [self.first_name.trim(), self.last_name.trim()]
+            .into_iter()
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if full_name.is_empty() && self.organization.trim().is_empty() {
+            return Err(Error::Payload("vyplň jméno nebo organizaci".into()));
+        }
+        let phones = self.phones.iter().map(|p| p.trim()).filter(|p| !p.is_empty());
+        let emails = self.emails.iter().map(|e| e.trim()).filter(|e| !e.is_empty());
+        let (street, city, zip, country) =
+            (self.street.trim(), self.city.trim(), self.postal_code.trim(), self.country.trim());
+        let has_address = [street, city, zip, country].iter().any(|s| !s.is_empty());
+        let url = self.url.trim();
+
+        if self.format == ContactFormat::MeCard {
+            // N: příjmení,jméno; ostatní pole se mohou opakovat
+            let name = [self.last_name.trim(), self.first_name.trim()]
+                .into_iter()
+                .filter(|s| !s.is_empty())
+                .map(escape_mecard)
+                .collect::<Vec<_>>()
+                .join(",");
+            let adr = [street, city, zip, country].into_iter().filter(|s| !s.is_empty()).collect::<Vec<_>>().join(", ");
+            let mut fields = vec![("N", name), ("ORG", escape_mecard(self.organization.trim()))];
+            fields.extend(phones.map(|p| ("TEL", escape_mecard(p))));
+            fields.extend(emails.map(|e| ("EMAIL", escape_mecard(e))));
+            fields.push(("ADR", escape_mecard(&adr)));
+            fields.push(("URL", escape_mecard(url)));
+
+            let mut out = String::from("MECARD:");
+            for (key, value) in fields.into_iter().filter(|(_, v)| !v.is_empty()) {
+                out.push_str(&format!("{key}:{value};"));
+            }
+            out.push(';');
+            return Ok(out);
+        }
+
+        let v4 = self.format == ContactFormat::VCard4;
+        let mut lines = vec!["BEGIN:VCARD".to_string(), format!("VERSION:{}", if v4 { "4.0" } else { "3.0" })];
+        lines.push(format!("N:{};{};;;", escape_vcard(self.last_name.trim()), escape_vcard(self.first_name.trim())));
+        let fn_value = if full_name.is_empty() { self.organization.trim() } else { &full_name };
+        lines.push(format!("FN:{}", escape_vcard(fn_value)));
+        if !self.organization.trim().is_empty() {
+            lines.push(format!("ORG:{}", escape_vcard(self.organization.trim())));
+        }
+        for p in phones {
+            // 4.0 zapisuje telefon jako URI tel:
+            lines.push(if v4 { format!("TEL;VALUE=uri:tel:{}", p.replace(' ', "")) } else { format!("TEL:{p}") });
+        }
+        for e in emails {
+            lines.push(format!("EMAIL:{}", escape_vcard(e)));
+        }
+        if has_address {
+            lines.push(format!(
+                "ADR:;;{};{};;{};{}",
+                escape_vcard(street),
+                escape_vcard(city),
+                escape_vcard(zip),
+                escape_vcard(country)
+            ));
+        }
+        if !url.is_empty() {
+            lines.push(format!("URL:{url}"));
+        }
+        lines.push("END:VCARD".into());
+        Ok(lines.join("\r\n"))
+    }
+}
+
+/// Escapování textových hodnot vCard (`\ , ;` a konce řádků).
+fn escape_vcard(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '\\' | ',' | ';' => {
+                out.push('\\');
+                out.push(ch);
+            }
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Česká QR Platba (Short Payment Descriptor, `SPD*1.0*…`) podle standardu ČBA.
+/// Pole jsou texty z formuláře; `payload()` je ověří a převede na kanonický tvar.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct PaymentPayload {
+    pub iban: String,
+    pub amount: String,   // „1 234,50“ i „1234.50“; prázdné = částku zadá plátce
+    pub currency: String, // ISO 4217, prázdné = CZK
+    pub variable_symbol: String,
+    pub constant_symbol: String,
+    pub specific_symbol: String,
+    pub message: String,  // zpráva pro příjemce, max. 60 znaků
+    pub due_date: String, // RRRR-MM-DD, D.M.RRRR nebo RRRRMMDD
+}
+
+impl PaymentPayload {
+    pub fn payload(&self) -> Result<String> {
+        let mut out = format!("SPD*1.0*ACC:{}*", normalize_iban(&self.iban)?);
+        // klíče za ACC abecedně (kanonický tvar pro případný CRC32)
+        if let Some(am) = parse_amount(&self.amount, 7)? {
+            out.push_str(&format!("AM:{am}*"));
+        }
+        let cc = self.currency.trim().to_ascii_uppercase();
+        let cc = if cc.is_empty() { "CZK".to_string() } else { cc };
+        if cc.len() != 3 || !cc.bytes().all(|b| b.is_ascii_uppercase()) {
+            return Err(Error::Payload(format!("neplatná měna „{cc}“ (třípísmenný kód, např. CZK)")));
+        }
+        out.push_str(&format!("CC:{cc}*"));
+        if let Some(dt) = parse_due_date(&self.due_date)? {
+            out.push_str(&format!("DT:{dt}*"));
+        }
+        let msg = self.message.trim();
+        if msg.chars().count() > 60 {
+            return Err(Error::Payload("zpráva pro příjemce má víc než 60 znaků".into()));
+        }
+        if !msg.is_empty() {
+            out.push_str(&format!("MSG:{}*", msg.replace('*', "%2A")));
+        }
+        for (key, label, value) in [
+            ("X-KS", "konstantní symbol", &self.constant_symbol),
+            ("X-SS", "specifický symbol", &self.specific_symbol),
+            ("X-VS", "variabilní symbol", &self.variable_symbol),
+        ] {
+            let v = value.trim();
+            if v.is_empty() {
+                continue;
+            }
+            if v.len() > 10 || !v.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(Error::Payload(format!("{label} smí mít nejvýš 10 číslic")));
+            }
+            out.push_str(&format!("{key}:{v}*"));
+        }
+        out.pop(); // poslední `*`
+        Ok(out)
+    }
+}
+
+/// Platba SEPA podle EPC069-12 („GiroCode“), verze 002 v UTF-8.
+/// Reference a volná zpráva se vylučují; standard předepisuje korekci chyb [`EpcPayload::EC_LEVEL`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct EpcPayload {
+    pub bic: String, // v rámci EHP nepovinný
+    pub beneficiary: String,
+    pub iban: String,
+    pub amount: String,    // v EUR; prázdné = částku zadá plátce
+    pub purpose: String,   // kód účelu ISO 20022 (4 znaky), např. GDDS
+    pub reference: String, // strukturovaná reference (RF…), max. 35 znaků
+    pub text: String,      // volná zpráva, max. 140 znaků
+}
+
+impl EpcPayload {
+    /// Úroveň korekce chyb, kterou EPC069-12 vyžaduje.
+    pub const EC_LEVEL: EcLevel = EcLevel::M;
+
+    pub fn payload(&self) -> Result<String> {
+        let bic = self.bic.trim().to_ascii_uppercase();
+        if !bic.is_empty() {
+            let b = bic.as_bytes();
+            if !(b.len() == 8 || b.len() == 11)
+                || !b[..6].iter().all(u8::is_ascii_uppercase)
+                || !b.iter().all(u8::is_ascii_alphanumeric)
+            {
+                return Err(Error::Payload(format!("„{bic}“ není BIC (8 nebo 11 znaků)")));
+            }
+        }
+        let name = self.beneficiary.trim();
+        if name.is_empty() {
+            return Err(Error::Payload("chybí jméno příjemce".into()));
+        }
+        let iban = normalize_iban(&self.iban)?;
+        let amount = parse_amount(&self.amount, 9)?.map(|am| format!("EUR{am}")).unwrap_or_default();
+        let purpose = self.purpose.trim().to_ascii_uppercase();
+        if !purpose.is_empty() && (purpose.len() != 4 || !purpose.bytes().all(|b| b.is_ascii_alphanumeric())) {
+            return Err(Error::Payload(format!("kód účelu „{purpose}“ musí mít 4 znaky")));
+        }
+        let (reference, text) = (self.reference.trim(), self.text.trim());
+        if !reference.is_empty() && !text.is_empty() {
+            return Err(Error::Payload("vyplň buď referenci, nebo zprávu – ne obojí".into()));
+        }
+        for (label, value, max) in
+            [("jméno příjemce", name, 70), ("reference", reference, 35), ("zpráva", text, 140)]
+        {
+            if value.chars().count() > max {
+                return Err(Error::Payload(format!("{label} má víc než {max} znaků")));
+            }
+            if value.contains(['\n', '\r']) {
+                return Err(Error::Payload(format!("{label} nesmí obsahovat konec řádku")));
+            }
+        }
+
+        let lines = ["BCD", "002", "1", "SCT", &bic, name, &iban, &amount, &purpose, reference, text];
+        // prázdné řádky na konci se vynechávají
+        let used = lines.iter().rposition(|l| !l.is_empty()).map_or(0, |i| i + 1);
+        let out = lines[..used].join("\n");
+        if out.len() > 331 {
+            return Err(Error::Payload("obsah je delší než 331 bajtů, které EPC dovoluje".into()));
+        }
+        Ok(out)
+    }
+}
+
+/// Událost do kalendáře (iCalendar `BEGIN:VEVENT` bez obalu VCALENDAR, jak ji čtou čtečky QR).
+/// Bez obalu nejde přiložit VTIMEZONE, proto se čas v zadaném pásmu převede na UTC (`…Z`).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct EventPayload {
+    pub title: String,
+    pub start: String,     // „2026-11-20 19:00“ nebo „20.11.2026 19:00“; bez času = celodenní
+    pub end: String,       // prázdné = bez konce; u celodenní akce poslední den (včetně)
+    pub time_zone: String, // IANA název (Europe/Prague), „UTC“, prázdné = čas zařízení (plovoucí)
+    pub location: String,
+    pub description: String,
+}
+
+impl EventPayload {
+    /// Obsah s časem vytvoření (DTSTAMP) = teď.
+    pub fn payload(&self) -> Result<String> {
+        self.payload_stamped(SystemTime::now())
+    }
+
+    /// Obsah s daným časem vytvoření (DTSTAMP), aby se QR neměnil s každým vykreslením.
+    /// UID je otisk údajů události – stejná událost má stejné UID i po opětovném načtení.
+    pub fn payload_stamped(&self, created: SystemTime) -> Result<String> {
+        let title = self.title.trim();
+        if title.is_empty() {
+            return Err(Error::Payload("chybí název události".into()));
+        }
+        if self.start.trim().is_empty() {
+            return Err(Error::Payload("chybí začátek události".into()));
+        }
+        let start = parse_date_time(&self.start)?;
+        let end = match self.end.trim() {
+            "" => None,
+            s => Some(parse_date_time(s)?),
+        };
+
+        let zone = match self.time_zone.trim() {
+            "" => None,
+            t if t.eq_ignore_ascii_case("utc") || t == "Z" => Some(TimeZone::UTC),
+            t => Some(
+                TimeZone::get(t)
+                    .map_err(|_| Error::Payload(format!("neznámé časové pásmo „{t}“ (např. Europe/Prague)")))?,
+            ),
+        };
+        let date_time = |date: Date, time: Time| match &zone {
+            Some(zone) => to_utc(date, time, zone),
+            None => Ok(format!("{}T{}", fmt_date(date), fmt_time(time))),
+        };
+        let stamp = Timestamp::try_from(created).unwrap_or(Timestamp::UNIX_EPOCH);
+
+        let mut lines = vec![
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}@kju-ar", self.uid()),
+            format!("DTSTAMP:{}", stamp.strftime("%Y%m%dT%H%M%SZ")),
+            format!("SUMMARY:{}", escape_vcard(title)),
+        ];
+        match (start, end) {
+            ((date, None), end) => {
+                // celodenní – DTEND je v iCalendar první den po akci
+                lines.push(format!("DTSTART;VALUE=DATE:{}", fmt_date(date)));
+                match end {
+                    Some((end, None)) if end < date => return Err(end_before_start()),
+                    Some((end, None)) => lines.push(format!("DTEND;VALUE=DATE:{}", fmt_date(next_day(end)))),
+                    Some(_) => return Err(mixed_all_day()),
+                    None => {}
+                }
+            }
+            ((date, Some(time)), end) => {
+                lines.push(format!("DTSTART:{}", date_time(date, time)?));
+                match end {
+                    Some((d, Some(t))) if (d, t) <= (date, time) => return Err(end_before_start()),
+                    Some((d, Some(t))) => lines.push(format!("DTEND:{}", date_time(d, t)?)),
+                    Some(_) => return Err(mixed_all_day()),
+                    None => {}
+                }
+            }
+        }
+        if !self.location.trim().is_empty() {
+            lines.push(format!("LOCATION:{}", escape_vcard(self.location.trim())));
+        }
+        if !self.description.trim().is_empty() {
+            lines.push(format!("DESCRIPTION:{}", escape_vcard(self.description.trim())));
+        }
+        lines.push("END:VEVENT".into());
+        Ok(lines.join("\r\n"))
+    }
+
+    /// Otisk údajů události (prvních 10 bajtů SHA-1 hexadecimálně).
+    fn uid(&self) -> String {
+        let mut hasher = Sha1::new();
+        for field in [&self.title, &self.start, &self.end, &self.time_zone, &self.location, &self.description] {
+            hasher.update(field.trim().as_bytes());
+            hasher.update([0]);
+        }
+        hasher.finalize()[..10].iter().map(|b| format!("{b:02x}")).collect()
+    }
+}
+
+type Date = (u32, u32, u32);
+type Time = (u32, u32, u32);
+
+fn end_before_start() -> Error {
+    Error::Payload("konec události je dřív než začátek".into())
+}
+
+fn mixed_all_day() -> Error {
+    Error::Payload("začátek i konec musí mít čas, nebo oba žádný (celodenní akce)".into())
+}
+
+/// Datum s nepovinným časem (`HH:MM` nebo `HH:MM:SS`) za mezerou nebo `T`.
+fn parse_date_time(s: &str) -> Result<(Date, Option<Time>)> {
+    let s = s.trim();
+    let (date, time) = match s.rsplit_once(|c: char| c.is_whitespace() || c == 'T') {
+        Some((d, t)) if t.contains(':') => (d, Some(t)),
+        _ => (s, None),
+    };
+    // „20. 11. 2026“ – mezery v českém zápisu data
+    let date = parse_date(&date.chars().filter(|c| !c.is_whitespace()).collect::<String>())?;
+    let Some(time) = time else { return Ok((date, None)) };
+    let invalid = || Error::Payload(format!("neplatný čas „{time}“ (HH:MM)"));
+    let parts = time
+        .split(':')
+        .map(|v| v.parse::<u32>().ok().filter(|_| !v.is_empty() && v.len() <= 2))
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(invalid)?;
+    let (h, m, sec) = match parts[..] {
+        [h, m] => (h, m, 0),
+        [h, m, sec] => (h, m, sec),
+        _ => return Err(invalid()),
+    };
+    if h > 23 || m > 59 || sec > 59 {
+        return Err(invalid());
+    }
+    Ok((date, Some((h, m, sec))))
+}
+
+fn next_day((y, m, d): Date) -> Date {
+    if d < days_in_month(y, m) {
+        (y, m, d + 1)
+    } else if m < 12 {
+        (y, m + 1, 1)
+    } else {
+        (y + 1, 1, 1)
+    }
+}
+
+fn fmt_date((y, m, d): Date) -> String {
+    format!("{y:04}{m:02}{d:02}")
+}
+
+fn fmt_time((h, m, s): Time) -> String {
+    format!("{h:02}{m:02}{s:02}")
+}
+
+/// Místní čas v pásmu `zone` jako UTC (`RRRRMMDDTHHMMSSZ`); v mezeře při přechodu
+/// na letní čas se posune dopředu, u dvojznačného času platí dřívější.
+fn to_utc((y, m, d): Date, (h, mi, s): Time, zone: &TimeZone) -> Result<String> {
+    let invalid = |e: jiff::Error| Error::Payload(format!("nejde převést čas do UTC: {e}"));
+    let local = civil::DateTime::new(y as i16, m as i8, d as i8, h as i8, mi as i8, s as i8, 0).map_err(invalid)?;
+    let utc = local.to_zoned(zone.clone()).map_err(invalid)?.timestamp();
+    Ok(utc.strftime("%Y%m%dT%H%M%SZ").to_string())
+}
+
+/// IBAN bez mezer velkými písmeny; ověří délku a kontrolní číslice (mod 97).
+pub(crate) fn normalize_iban(s: &str) -> Result<String> {
+    let iban: String = s.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_ascii_uppercase();
+    if iban.is_empty() {
+        return Err(Error::Payload("chybí IBAN".into()));
+    }
+    let b = iban.as_bytes();
+    if !(15..=34).contains(&b.len())
+        || !b[..2].iter().all(u8::is_ascii_uppercase)
+        || !b[2..4].iter().all(u8::is_ascii_digit)
+        || !b.iter().all(u8::is_ascii_alphanumeric)
+    {
+        return Err(Error::Payload(format!("„{iban}“ není IBAN")));
+    }
+    // první čtyři znaky na konec, písmena → 10..35, zbytek po 97 musí být 1
+    let rem = b[4..].iter().chain(&b[..4]).fold(0u32, |acc, &c| {
+        if c.is_ascii_digit() {
+            (acc * 10 + (c - b'0') as u32) % 97
+        } else {
+            (acc * 100 + (c - b'A' + 10) as u32) % 97
+        }
+    });
+    if rem != 1 {
+        return Err(Error::Payload(format!("IBAN {iban} má chybné kontrolní číslice")));
+    }
+    Ok(iban)
+}
+
+/// Částka na dvě desetinná místa (`1234.50`); mezery a desetinná čárka jsou povolené.
+/// `max_digits` = nejvýš číslic před desetinnou tečkou.
+fn parse_amount(s: &str, max_digits: usize) -> Result<Option<String>> {
+    let s: String = s.chars().filter(|c| !c.is_whitespace()).map(|c| if c == ',' { '.' } else { c }).collect();
+    if s.is_empty() {
+        return Ok(None);
+    }
+    let invalid = || Error::Payload(format!("neplatná částka „{s}“"));
+    let (whole, frac) = s.split_once('.').unwrap_or((&s, ""));
+    if whole.is_empty()
+        || frac.len() > 2
+        || !whole.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+    let whole = whole.trim_start_matches('0');
+    let whole = if whole.is_empty() { "0" } else { whole };
+    if whole.len() > max_digits {
+        return Err(Error::Payload(format!("částka je příliš vysoká (max. {max_digits} číslic před čárkou)")));
+    }
+    let am = format!("{whole}.{frac:0<2}");
+    if am == "0.00" {
+        return Err(Error::Payload("částka musí být větší než nula".into()));
+    }
+    Ok(Some(am))
+}
+
+/// Datum splatnosti jako `RRRRMMDD`; přijímá i `RRRR-MM-DD` a `D.M.RRRR`.
+fn parse_due_date(s: &str) -> Result<Option<String>> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(fmt_date(parse_date(s)?)))
+}
+
+/// Kalendářní datum `RRRR-MM-DD`, `D.M.RRRR` nebo `RRRRMMDD` → (rok, měsíc, den).
+fn parse_date(s: &str) -> Result<(u32, u32, u32)> {
+    let invalid = || Error::Payload(format!("neplatné datum „{s}“ (RRRR-MM-DD nebo D.M.RRRR)"));
+    let parts: Vec<&str> = if s.contains('-') {
+        s.split('-').collect()
+    } else if s.contains('.') {
+        s.split('.').map(str::trim).rev().collect()
+    } else if s.len() == 8 && s.is_ascii() {
+        vec![&s[..4], &s[4..6], &s[6..]]
+    } else {
+        return Err(invalid());
+    };
+    let [y, m, d] = parts[..] else { return Err(invalid()) };
+    let num = |v: &str| v.parse::<u32>().ok().filter(|_| v.bytes().all(|b| b.is_ascii_digit()));
+    let (Some(y), Some(m), Some(d)) = (num(y), num(m), num(d)) else { return Err(invalid()) };
+    if !(1000..=9999).contains(&y) || d == 0 || d > days_in_month(y, m) {
+        return Err(invalid());
+    }
+    Ok((y, m, d))
+}
+
+/// Počet dní v měsíci; 0 pro neplatný měsíc.
+fn days_in_month(y: u32, m: u32) -> u32 {
+    let leap = y.is_multiple_of(4) && (!y.is_multiple_of(100) || y.is_multiple_of(400));
+    match m {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mecard_escaping() {
+        assert_eq!(escape_mecard(r#"a;b,c:d\e"f"#), r#"a\;b\,c\:d\\e\"f"#);
+        assert_eq!(escape_mecard("řádek\ndruhý"), "řádek\ndruhý");
+    }
+
+    #[test]
+    fn vcard_escaping() {
+        assert_eq!(escape_vcard(r"a;b,c:d\e"), r"a\;b\,c:d\\e");
+        assert_eq!(escape_vcard("Na Příkopě 1\r\n2. patro"), r"Na Příkopě 1\n2. patro");
+    }
+
+    #[test]
+    fn wifi() {
+        let wifi = WifiPayload { ssid: "Kavárna;U:Lípy".into(), password: r#"pa\ss,"1""#.into(), ..WifiPayload::default() };
+        assert_eq!(wifi.payload().unwrap(), r#"WIFI:T:WPA;S:Kavárna\;U\:Lípy;P:pa\\ss\,\"1\";;"#);
+
+        let open = WifiPayload { ssid: "Host".into(), password: "ignorováno".into(), security: WifiSecurity::None, hidden: true };
+        assert_eq!(open.payload().unwrap(), "WIFI:T:nopass;S:Host;H:true;;");
+
+        let wep = WifiPayload { ssid: "Stará".into(), password: "12345".into(), security: WifiSecurity::Wep, hidden: false };
+        assert_eq!(wep.payload().unwrap(), "WIFI:T:WEP;S:Stará;P:12345;;");
+    }
+
+    #[test]
+    fn wifi_rejects_missing_fields() {
+        assert!(WifiPayload { password: "x".into(), ..WifiPayload::default() }.payload().is_err());
+        assert!(WifiPayload { ssid: "Síť".into(), ..WifiPayload::default() }.payload().is_err());
+    }
+
+    #[test]
+    fn mailto() {
+        let mail = |to: &str, subject: &str, body: &str| {
+            EmailPayload { to: to.into(), subject: subject.into(), body: body.into() }.payload()
+        };
+        let cases = [
+            ("jan@firma.cz", "", "", "mailto:jan@firma.cz"),
+            (
+                "jan@firma.cz; eva+news@firma.cz,\n",
+                "Dotaz & otázka?",
+                "Dobrý den,\nděkuji",
+                "mailto:jan@firma.cz,eva+news@firma.cz?subject=Dotaz%20%26%20ot%C3%A1zka%3F\
+                 &body=Dobr%C3%BD%20den%2C%0D%0Ad%C4%9Bkuji",
+            ),
+            ("a@b.cz", "", "řádek\r\ndruhý=2", "mailto:a@b.cz?body=%C5%99%C3%A1dek%0D%0Adruh%C3%BD%3D2"),
+            ("a@b.cz", " jen předmět ", "", "mailto:a@b.cz?subject=jen%20p%C5%99edm%C4%9Bt"),
+        ];
+        for (to, subject, body, out) in cases {
+            assert_eq!(mail(to, subject, body).unwrap(), out, "{to}");
+        }
+        for bad in ["", " ; ", "jan.firma.cz", "jan novak@firma.cz"] {
+            assert!(mail(bad, "x", "").is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn sms() {
+        let sms = |format, message: &str| {
+            SmsPayload { format, number: "+420 601 234 567".into(), message: message.into() }.payload().unwrap()
+        };
+        let cases = [
+            (SmsFormat::Smsto, "Ahoj: 50 % & ?", "SMSTO:+420601234567:Ahoj: 50 % & ?"),
+            (SmsFormat::Smsto, "", "SMSTO:+420601234567:"),
+            (SmsFormat::Uri, "Ahoj: 50 % & ?", "sms:+420601234567?body=Ahoj%3A%2050%20%25%20%26%20%3F"),
+            (SmsFormat::Uri, "řádek\ndruhý", "sms:+420601234567?body=%C5%99%C3%A1dek%0Adruh%C3%BD"),
+            (SmsFormat::Uri, "  ", "sms:+420601234567"),
+        ];
+        for (format, message, out) in cases {
+            assert_eq!(sms(format, message), out, "{format:?} {message}");
+        }
+        assert!(SmsPayload { message: "text".into(), ..SmsPayload::default() }.payload().is_err());
+    }
+
+    #[test]
+    fn tel() {
+        let tel = |n: &str| PhonePayload { number: n.into() }.payload();
+        for (input, out) in [
+            ("+420 601 234 567", "tel:+420601234567"),
+            (" +420 (601) 234-567 ", "tel:+420601234567"),
+            ("601/234.567", "tel:601234567"),
+            ("112", "tel:112"),
+        ] {
+            assert_eq!(tel(input).unwrap(), out, "{input}");
+        }
+        for bad in ["", "  ", "12", "++420601234567", "420+601", "+420 60a", "1234567890123456"] {
+            assert!(tel(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn geo() {
+        let geo = |lat: &str, lon: &str| GeoPayload { latitude: lat.into(), longitude: lon.into() }.payload();
+        for (lat, lon, out) in [
+            ("50.0875", "14.4213", "geo:50.0875,14.4213"),
+            ("50,0875", " 14,4213 ", "geo:50.0875,14.4213"), // desetinná čárka
+            ("-90", "+180", "geo:-90,180"),
+            ("90", "-180", "geo:90,-180"),
+        ] {
+            assert_eq!(geo(lat, lon).unwrap(), out, "{lat} {lon}");
+        }
+        for (lat, lon) in [("90.0001", "0"), ("-91", "0"), ("0", "180.5"), ("0", "-181"), ("", "1"), ("1", " ")] {
+            assert!(geo(lat, lon).is_err(), "{lat} {lon}");
+        }
+        for bad in ["1e1", "abc", "50,08,1", "NaN", "inf"] {
+            assert!(geo(bad, "0").is_err(), "{bad}");
+        }
+    }
+
+    fn contact(format: ContactFormat) -> ContactPayload {
+        ContactPayload {
+            format,
+            first_name: "Jan".into(),
+            last_name: "Novák; st.".into(),
+            organization: "Firma, s.r.o.".into(),
+            phones: vec!["+420 601 234 567".into(), " ".into()],
+            emails: vec!["jan@firma.cz".into()],
+            street: "Dlouhá 5\nvchod B".into(),
+            city: "Praha".into(),
+            postal_code: "110 00".into(),
+            country: "CZ".into(),
+            url: "https://firma.cz/a:b".into(),
+        }
+    }
+
+    #[test]
+    fn vcard3() {
+        assert_eq!(
+            contact(ContactFormat::VCard3).payload().unwrap(),
+            [
+                "BEGIN:VCARD",
+                "VERSION:3.0",
+                r"N:Novák\; st.;Jan;;;",
+                r"FN:Jan Novák\; st.",
+                r"ORG:Firma\, s.r.o.",
+                "TEL:+420 601 234 567",
+                "EMAIL:jan@firma.cz",
+                r"ADR:;;Dlouhá 5\nvchod B;Praha;;110 00;CZ",
+                "URL:https://firma.cz/a:b",
+                "END:VCARD",
+            ]
+            .join("\r\n")
+        );
+    }
+
+    #[test]
+    fn vcard4() {
+        let v = contact(ContactFormat::VCard4).payload().unwrap();
+        assert!(v.starts_with("BEGIN:VCARD\r\nVERSION:4.0\r\n"), "{v}");
+        assert!(v.contains("\r\nTEL;VALUE=uri:tel:+420601234567\r\n"), "{v}");
+        assert!(v.split("\r\n").all(|l| !l.contains('\n')), "{v}"); // konec řádku v adrese jen jako \n
+    }
+
+    #[test]
+    fn mecard() {
+        assert_eq!(
+            contact(ContactFormat::MeCard).payload().unwrap(),
+            concat!(
+                r"MECARD:N:Novák\; st.,Jan;ORG:Firma\, s.r.o.;TEL:+420 601 234 567;EMAIL:jan@firma.cz;",
+                "ADR:Dlouhá 5\nvchod B\\, Praha\\, 110 00\\, CZ;URL:https\\://firma.cz/a\\:b;;"
+            )
+        );
+    }
+
+    #[test]
+    fn contact_needs_name_or_organization() {
+        assert!(ContactPayload::default().payload().is_err());
+        let org = ContactPayload { organization: "Firma".into(), ..ContactPayload::default() };
+        assert!(org.payload().unwrap().contains("\r\nFN:Firma\r\n"));
+    }
+
+    fn payment(iban: &str, amount: &str) -> PaymentPayload {
+        PaymentPayload { iban: iban.into(), amount: amount.into(), ..PaymentPayload::default() }
+    }
+
+    #[test]
+    fn spayd_examples_from_cba_spec() {
+        let p = PaymentPayload {
+            message: "PLATBA ZA ZBOZI".into(),
+            due_date: "2012-05-24".into(),
+            ..payment("CZ58 5500 0000 0012 6509 8001", "480,50")
+        };
+        assert_eq!(p.payload().unwrap(), "SPD*1.0*ACC:CZ5855000000001265098001*AM:480.50*CC:CZK*DT:20120524*MSG:PLATBA ZA ZBOZI");
+
+        let p = PaymentPayload {
+            message: "PLATBA ZA ZBOZI".into(),
+            variable_symbol: "1234567890".into(),
+            ..payment("CZ2806000000000168540115", "450")
+        };
+        assert_eq!(
+            p.payload().unwrap(),
+            "SPD*1.0*ACC:CZ2806000000000168540115*AM:450.00*CC:CZK*MSG:PLATBA ZA ZBOZI*X-VS:1234567890"
+        );
+    }
+
+    #[test]
+    fn spayd_optional_fields_and_key_order() {
+        assert_eq!(payment("cz6508000000192000145399", "").payload().unwrap(), "SPD*1.0*ACC:CZ6508000000192000145399*CC:CZK");
+        let p = PaymentPayload {
+            currency: "eur".into(),
+            variable_symbol: "11".into(),
+            constant_symbol: "0308".into(),
+            specific_symbol: "22".into(),
+            message: "Faktura *7*".into(),
+            due_date: "1.2.2027".into(),
+            ..payment("CZ6508000000192000145399", "1 234,5")
+        };
+        assert_eq!(
+            p.payload().unwrap(),
+            "SPD*1.0*ACC:CZ6508000000192000145399*AM:1234.50*CC:EUR*DT:20270201*MSG:Faktura %2A7%2A*X-KS:0308*X-SS:22*X-VS:11"
+        );
+    }
+
+    #[test]
+    fn spayd_rejects_invalid_fields() {
+        let ok = payment("CZ6508000000192000145399", "100");
+        assert!(payment("CZ6508000000192000145398", "100").payload().is_err()); // kontrolní číslice
+        let msg = |n: usize| PaymentPayload { message: "x".repeat(n), ..ok.clone() };
+        assert!(msg(60).payload().is_ok());
+        assert!(msg(61).payload().is_err());
+        assert!(PaymentPayload { message: "ř".repeat(60), ..ok.clone() }.payload().is_ok()); // znaky, ne bajty
+        assert!(PaymentPayload { currency: "KČ".into(), ..ok.clone() }.payload().is_err());
+        assert!(PaymentPayload { variable_symbol: "12345678901".into(), ..ok.clone() }.payload().is_err());
+        assert!(PaymentPayload { variable_symbol: "12a".into(), ..ok.clone() }.payload().is_err());
+        assert!(PaymentPayload { due_date: "30.2.2027".into(), ..ok.clone() }.payload().is_err());
+        assert!(payment("CZ6508000000192000145399", "12345678").payload().is_err()); // max. 7 číslic
+    }
+
+    #[test]
+    fn iban_normalization() {
+        assert_eq!(normalize_iban(" cz65 0800 0000 1920 0014 5399 ").unwrap(), "CZ6508000000192000145399");
+        assert_eq!(normalize_iban("DE89 3704 0044 0532 0130 00").unwrap(), "DE89370400440532013000");
+        for bad in ["", "CZ65", "CZ6508000000192000145390", "C165080000001920001453", "CZ65-0800-0000-1920-0014-5399"] {
+            assert!(normalize_iban(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn amounts() {
+        for (input, out) in [("1", "1.00"), ("0,5", "0.50"), ("1 234.5", "1234.50"), ("007.10", "7.10"), ("9999999", "9999999.00")] {
+            assert_eq!(parse_amount(input, 7).unwrap().as_deref(), Some(out), "{input}");
+        }
+        assert_eq!(parse_amount("  ", 7).unwrap(), None);
+        for bad in ["0", "0,00", "1.234", "1,2,3", ",5", "-5", "1e3", "10000000"] {
+            assert!(parse_amount(bad, 7).is_err(), "{bad}");
+        }
+    }
+
+    fn epc(bic: &str, name: &str, iban: &str, amount: &str, purpose: &str, reference: &str, text: &str) -> EpcPayload {
+        let owned = |s: &str| s.to_string();
+        EpcPayload {
+            bic: owned(bic),
+            beneficiary: owned(name),
+            iban: owned(iban),
+            amount: owned(amount),
+            purpose: owned(purpose),
+            reference: owned(reference),
+            text: owned(text),
+        }
+    }
+
+    #[test]
+    fn epc_examples() {
+        // příklady z EPC069-12 (verze 002, částka vždy na dvě desetinná místa)
+        let cases = [
+            (
+                epc("BPOTBEB1", "Red Cross of Belgium", "BE72000000001616", "1", "CHAR", "", "Urgency fund"),
+                "BCD\n002\n1\nSCT\nBPOTBEB1\nRed Cross of Belgium\nBE72000000001616\nEUR1.00\nCHAR\n\nUrgency fund",
+            ),
+            (
+                epc("bhbldehhxxx", "Franz Mustermänn", "DE71 1102 2033 0123 4567 89", "12,3", "gdds", "RF18539007547034", ""),
+                "BCD\n002\n1\nSCT\nBHBLDEHHXXX\nFranz Mustermänn\nDE71110220330123456789\nEUR12.30\nGDDS\nRF18539007547034",
+            ),
+            (
+                // bez BIC, částky a účelu – prázdné řádky zůstanou, koncové se vynechají
+                epc("", "Wikimedia Foerdergesellschaft", "DE33100205000001194700", "", "", "", ""),
+                "BCD\n002\n1\nSCT\n\nWikimedia Foerdergesellschaft\nDE33100205000001194700",
+            ),
+        ];
+        for (payload, expected) in cases {
+            assert_eq!(payload.payload().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn epc_limits() {
+        let iban = "AT611904300234573201";
+        let name = |n: usize| "N".repeat(n);
+        let cases: [(EpcPayload, bool); 16] = [
+            (epc("GIBAATWW", "Anna", iban, "", "", "", ""), true),
+            (epc("GIBAATWWXXX", "Anna", iban, "", "", "", ""), true),
+            (epc("GIBAATW", "Anna", iban, "", "", "", ""), false), // 7 znaků
+            (epc("GIBAATWWXX", "Anna", iban, "", "", "", ""), false), // 10 znaků
+            (epc("GIBA1TWW", "Anna", iban, "", "", "", ""), false), // číslice v kódu banky/země
+            (epc("", &name(70), iban, "", "", "", ""), true),
+            (epc("", &name(71), iban, "", "", "", ""), false),
+            (epc("", "", iban, "", "", "", ""), false),
+            (epc("", "Anna", iban, "", "", &"R".repeat(35), ""), true),
+            (epc("", "Anna", iban, "", "", &"R".repeat(36), ""), false),
+            (epc("", "Anna", iban, "", "", "", &"t".repeat(140)), true),
+            (epc("", "Anna", iban, "", "", "", &"t".repeat(141)), false),
+            (epc("", "Anna", iban, "", "", "RF18539007547034", "Díky"), false), // reference i zpráva
+            (epc("", "Anna", iban, "999999999.99", "", "", ""), true),
+            (epc("", "Anna", iban, "1000000000", "", "", ""), false),
+            (epc("", "Anna", "AT611904300234573202", "", "", "", ""), false), // kontrolní číslice
+        ];
+        for (i, (payload, ok)) in cases.iter().enumerate() {
+            assert_eq!(payload.payload().is_ok(), *ok, "případ {i}: {payload:?}");
+        }
+    }
+
+    #[test]
+    fn epc_payload_size() {
+        // 70 + 140 dvoubajtových znaků se do 331 bajtů nevejde, i když délky v znacích sedí
+        let long = epc("GIBAATWWXXX", &"ř".repeat(70), "AT611904300234573201", "1", "GDDS", "", &"ž".repeat(140));
+        assert!(long.payload().is_err());
+        let fits = EpcPayload { text: "ž".repeat(60), ..long };
+        assert!(fits.payload().unwrap().len() <= 331);
+        assert!(epc("", "Anna", "AT611904300234573201", "", "", "", "a\nb").payload().is_err());
+    }
+
+    fn event(start: &str, end: &str, tz: &str) -> EventPayload {
+        EventPayload {
+            title: "Koncert; sál 2".into(),
+            start: start.into(),
+            end: end.into(),
+            time_zone: tz.into(),
+            ..EventPayload::default()
+        }
+    }
+
+    /// 2026-10-17 08:30:00 UTC
+    fn stamp() -> SystemTime {
+        SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_792_225_800)
+    }
+
+    #[test]
+    fn date_time_formats() {
+        for s in ["2026-11-20 19:00", "20.11.2026 19:00", "20. 11. 2026 19:00", "2026-11-20T19:00", "20261120 19:00:00"] {
+            assert_eq!(parse_date_time(s).unwrap(), ((2026, 11, 20), Some((19, 0, 0))), "{s}");
+        }
+        assert_eq!(parse_date_time(" 1.2.2027 ").unwrap(), ((2027, 2, 1), None));
+        assert_eq!(parse_date_time("29.2.2028 7:05:09").unwrap(), ((2028, 2, 29), Some((7, 5, 9))));
+        let bad = ["29.2.2027", "2026-13-01", "2026-11-20 24:00", "2026-11-20 19:60", "2026-11-20 19", "20.11. 19:00", "zítra"];
+        for bad in bad {
+            assert!(parse_date_time(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn next_day_crosses_boundaries() {
+        assert_eq!(next_day((2026, 11, 20)), (2026, 11, 21));
+        assert_eq!(next_day((2026, 4, 30)), (2026, 5, 1));
+        assert_eq!(next_day((2026, 12, 31)), (2027, 1, 1));
+        assert_eq!(next_day((2027, 2, 28)), (2027, 3, 1));
+        assert_eq!(next_day((2028, 2, 28)), (2028, 2, 29));
+        assert_eq!(next_day((2100, 2, 28)), (2100, 3, 1));
+    }
+
+    #[test]
+    fn event_in_time_zone_is_utc() {
+        let winter = event("20.11.2026 19:00", "20.11.2026 21:30", "Europe/Prague").payload_stamped(stamp()).unwrap();
+        let lines: Vec<&str> = winter.split("\r\n").collect();
+        assert_eq!(lines[0], "BEGIN:VEVENT");
+        assert!(lines[1].starts_with("UID:") && lines[1].ends_with("@kju-ar"), "{winter}");
+        assert_eq!(
+            lines[2..],
+            [
+                "DTSTAMP:20261017T083000Z",
+                r"SUMMARY:Koncert\; sál 2",
+                "DTSTART:20261120T180000Z",
+                "DTEND:20261120T203000Z",
+                "END:VEVENT",
+            ]
+        );
+        assert!(!winter.contains("TZID"));
+
+        let summer = event("2026-07-01 10:00", "", "Europe/Prague").payload_stamped(stamp()).unwrap();
+        assert!(summer.contains("\r\nDTSTART:20260701T080000Z\r\n"), "{summer}");
+        // přes půlnoc a konec roku
+        let nye = event("31.12.2026 23:30", "1.1.2027 0:30", "America/New_York").payload_stamped(stamp()).unwrap();
+        assert!(nye.contains("DTSTART:20270101T043000Z\r\nDTEND:20270101T053000Z"), "{nye}");
+        let utc = event("2026-11-20 19:00", "", "utc").payload_stamped(stamp()).unwrap();
+        assert!(utc.contains("DTSTART:20261120T190000Z"), "{utc}");
+        let floating = event("2026-11-20 19:00", "", "").payload_stamped(stamp()).unwrap();
+        assert!(floating.contains("DTSTART:20261120T190000\r\n"), "{floating}");
+        assert!(event("2026-11-20 19:00", "", "Europe/Brno").payload().is_err());
+    }
+
+    #[test]
+    fn all_day_event() {
+        let e = event("31.12.2026", "31.12.2026", "Europe/Prague").payload_stamped(stamp()).unwrap();
+        assert!(e.contains("DTSTART;VALUE=DATE:20261231\r\nDTEND;VALUE=DATE:20270101\r\n"), "{e}");
+        let e = event("2028-02-28", "2028-02-29", "").payload_stamped(stamp()).unwrap();
+        assert!(e.contains("DTEND;VALUE=DATE:20280301"), "{e}");
+    }
+
+    #[test]
+    fn event_rejects_end_before_start() {
+        for (start, end) in [
+            ("20.11.2026 19:00", "20.11.2026 18:59"),
+            ("20.11.2026 19:00", "20.11.2026 19:00"),
+            ("1.1.2027 0:30", "31.12.2026 23:30"),
+            ("2.3.2027", "1.3.2027"),
+        ] {
+            let err = event(start, end, "Europe/Prague").payload().unwrap_err();
+            assert!(err.to_string().contains("dřív než začátek"), "{start} – {end}: {err}");
+        }
+        assert!(event("20.11.2026", "20.11.2026 19:00", "").payload().is_err()); // celodenní s časem
+        assert!(EventPayload { title: " ".into(), ..event("20.11.2026", "", "") }.payload().is_err());
+    }
+
+    #[test]
+    fn event_uid_is_stable() {
+        let a = event("20.11.2026 19:00", "", "Europe/Prague");
+        let uid = |e: &EventPayload| e.payload_stamped(stamp()).unwrap().split("\r\n").nth(1).unwrap().to_string();
+        assert_eq!(uid(&a), uid(&a.clone()));
+        assert_ne!(uid(&a), uid(&EventPayload { location: "Rudolfinum".into(), ..a.clone() }));
+        // DTSTAMP je jediné, co se s časem mění
+        let later = a.payload_stamped(stamp() + std::time::Duration::from_secs(60)).unwrap();
+        assert_ne!(later, a.payload_stamped(stamp()).unwrap());
+        assert!(later.contains("DTSTAMP:20261017T083100Z"));
+    }
+}
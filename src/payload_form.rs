@@ -0,0 +1,318 @@
+//! Formuláře strukturovaného obsahu QR v GUI (Wi-Fi, kontakt, …) – z hodnot sestaví text pro QR.
+
+use eframe::egui;
+use egui::{ComboBox, TextEdit};
+use kju_ar::{
+    Caption, ContactFormat, ContactPayload, EcLevel, EmailPayload, EpcPayload, EventPayload, GeoPayload, PaymentPayload,
+    PhonePayload, SmsFormat, SmsPayload, WifiPayload, WifiSecurity,
+};
+use std::time::SystemTime;
+
+/// Co jednotlivý QR obsahuje.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum PayloadKind {
+    #[default]
+    Url, // volný text / odkaz z pole URL
+    Email,
+    Sms,
+    Phone,
+    Geo,
+    Wifi,
+    Contact,
+    Payment,
+    Epc,
+    Event,
+}
+
+impl PayloadKind {
+    pub const ALL: [PayloadKind; 10] = [
+        PayloadKind::Url,
+        PayloadKind::Email,
+        PayloadKind::Sms,
+        PayloadKind::Phone,
+        PayloadKind::Geo,
+        PayloadKind::Wifi,
+        PayloadKind::Contact,
+        PayloadKind::Event,
+        PayloadKind::Payment,
+        PayloadKind::Epc,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PayloadKind::Url => "Odkaz / text",
+            PayloadKind::Email => "E-mail",
+            PayloadKind::Sms => "SMS",
+            PayloadKind::Phone => "Telefonní číslo",
+            PayloadKind::Geo => "Poloha (GPS)",
+            PayloadKind::Wifi => "Wi-Fi síť",
+            PayloadKind::Contact => "Kontakt (vizitka)",
+            PayloadKind::Payment => "QR Platba",
+            PayloadKind::Epc => "Platba SEPA (EPC/GiroCode)",
+            PayloadKind::Event => "Událost v kalendáři",
+        }
+    }
+}
+
+/// Hodnoty všech formulářů (přepnutím typu se nic neztratí).
+pub struct PayloadForms {
+    pub kind: PayloadKind,
+    pub email: EmailPayload,
+    pub sms: SmsPayload,
+    pub phone: PhonePayload,
+    pub geo: GeoPayload,
+    pub wifi: WifiPayload,
+    pub contact: ContactPayload,
+    contact_phones: String, // po řádcích → `contact.phones`
+    contact_emails: String,
+    pub payment: PaymentPayload,
+    pub payment_frame: bool, // kód v rámečku s nápisem „QR Platba“
+    pub epc: EpcPayload,
+    pub event: EventPayload,
+    event_stamp: SystemTime, // DTSTAMP – poslední úprava události, ne každé vykreslení
+}
+
+impl Default for PayloadForms {
+    fn default() -> Self {
+        Self {
+            kind: PayloadKind::default(),
+            email: EmailPayload::default(),
+            sms: SmsPayload::default(),
+            phone: PhonePayload::default(),
+            geo: GeoPayload::default(),
+            wifi: WifiPayload::default(),
+            contact: ContactPayload::default(),
+            contact_phones: String::new(),
+            contact_emails: String::new(),
+            payment: PaymentPayload { currency: "CZK".into(), ..Default::default() },
+            payment_frame: true,
+            epc: EpcPayload::default(),
+            event: EventPayload { time_zone: "Europe/Prague".into(), ..Default::default() },
+            event_stamp: SystemTime::now(),
+        }
+    }
+}
+
+impl PayloadForms {
+    /// Text pro QR podle zvoleného typu (`url` = obsah pole URL).
+    pub fn payload(&self, url: &str) -> anyhow::Result<String> {
+        Ok(match self.kind {
+            PayloadKind::Url if url.trim().is_empty() => anyhow::bail!("Zadej URL pro QR"),
+            PayloadKind::Url => url.trim().to_string(),
+            PayloadKind::Email => self.email.payload()?,
+            PayloadKind::Sms => self.sms.payload()?,
+            PayloadKind::Phone => self.phone.payload()?,
+            PayloadKind::Geo => self.geo.payload()?,
+            PayloadKind::Wifi => self.wifi.payload()?,
+            PayloadKind::Contact => self.contact.payload()?,
+            PayloadKind::Payment => self.payment.payload()?,
+            PayloadKind::Epc => self.epc.payload()?,
+            PayloadKind::Event => self.event.payload_stamped(self.event_stamp)?,
+        })
+    }
+
+    /// Úroveň korekce chyb předepsaná formátem obsahu (volba v GUI se pak nepoužije).
+    pub fn required_ec(&self) -> Option<EcLevel> {
+        (self.kind == PayloadKind::Epc).then_some(EpcPayload::EC_LEVEL)
+    }
+
+    /// Popisek samostatného QR, který k typu obsahu patří.
+    pub fn caption(&self) -> Option<Caption<'static>> {
+        (self.kind == PayloadKind::Payment && self.payment_frame).then_some(Caption::PaymentFrame)
+    }
+
+    /// Vykreslí formulář zvoleného typu; vrací `true`, pokud se něco změnilo.
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        match self.kind {
+            PayloadKind::Url => false,
+            PayloadKind::Email => email_ui(ui, &mut self.email),
+            PayloadKind::Sms => sms_ui(ui, &mut self.sms),
+            PayloadKind::Phone => phone_ui(ui, &mut self.phone),
+            PayloadKind::Geo => geo_ui(ui, &mut self.geo),
+            PayloadKind::Wifi => wifi_ui(ui, &mut self.wifi),
+            PayloadKind::Contact => {
+                let changed = contact_ui(ui, &mut self.contact, &mut self.contact_phones, &mut self.contact_emails);
+                if changed {
+                    self.contact.phones = lines(&self.contact_phones);
+                    self.contact.emails = lines(&self.contact_emails);
+                }
+                changed
+            }
+            PayloadKind::Payment => payment_ui(ui, &mut self.payment, &mut self.payment_frame),
+            PayloadKind::Epc => epc_ui(ui, &mut self.epc),
+            PayloadKind::Event => {
+                let changed = event_ui(ui, &mut self.event);
+                if changed {
+                    self.event_stamp = SystemTime::now();
+                }
+                changed
+            }
+        }
+    }
+}
+
+fn email_ui(ui: &mut egui::Ui, e: &mut EmailPayload) -> bool {
+    let mut changed = false;
+    egui::Grid::new("email_form").num_columns(2).show(ui, |ui| {
+        changed |= text_row(ui, "Komu:", &mut e.to);
+        changed |= text_row(ui, "Předmět:", &mut e.subject);
+        changed |= lines_row(ui, "Text:", &mut e.body);
+    });
+    changed
+}
+
+fn sms_ui(ui: &mut egui::Ui, s: &mut SmsPayload) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label("Formát:");
+        for (f, label) in [(SmsFormat::Smsto, "SMSTO:"), (SmsFormat::Uri, "sms:")] {
+            changed |= ui.selectable_value(&mut s.format, f, label).changed();
+        }
+    });
+    egui::Grid::new("sms_form").num_columns(2).show(ui, |ui| {
+        changed |= text_row(ui, "Číslo:", &mut s.number);
+        changed |= lines_row(ui, "Text zprávy:", &mut s.message);
+    });
+    changed
+}
+
+fn phone_ui(ui: &mut egui::Ui, p: &mut PhonePayload) -> bool {
+    egui::Grid::new("phone_form").num_columns(2).show(ui, |ui| text_row(ui, "Číslo:", &mut p.number)).inner
+}
+
+fn geo_ui(ui: &mut egui::Ui, g: &mut GeoPayload) -> bool {
+    let mut changed = false;
+    egui::Grid::new("geo_form").num_columns(2).show(ui, |ui| {
+        changed |= text_row(ui, "Zeměpisná šířka:", &mut g.latitude);
+        changed |= text_row(ui, "Zeměpisná délka:", &mut g.longitude);
+    });
+    ui.weak("Stupně WGS 84 jako desetinné číslo, např. 50.0875 a 14.4214.");
+    changed
+}
+
+fn wifi_ui(ui: &mut egui::Ui, w: &mut WifiPayload) -> bool {
+    let mut changed = false;
+    egui::Grid::new("wifi_form").num_columns(2).show(ui, |ui| {
+        changed |= text_row(ui, "Název sítě (SSID):", &mut w.ssid);
+
+        ui.label("Zabezpečení:");
+        ComboBox::from_id_source("wifi_security")
+            .selected_text(security_label(w.security))
+            .show_ui(ui, |ui| {
+                for s in [WifiSecurity::Wpa, WifiSecurity::Wep, WifiSecurity::None] {
+                    changed |= ui.selectable_value(&mut w.security, s, security_label(s)).changed();
+                }
+            });
+        ui.end_row();
+
+        ui.label("Heslo:");
+        ui.add_enabled_ui(w.security != WifiSecurity::None, |ui| {
+            changed |= ui.add(TextEdit::singleline(&mut w.password).desired_width(f32::INFINITY)).changed();
+        });
+        ui.end_row();
+
+        ui.label("");
+        changed |= ui.checkbox(&mut w.hidden, "Skrytá síť (nevysílá SSID)").changed();
+        ui.end_row();
+    });
+    changed
+}
+
+fn security_label(s: WifiSecurity) -> &'static str {
+    match s {
+        WifiSecurity::Wpa => "WPA/WPA2/WPA3",
+        WifiSecurity::Wep => "WEP",
+        WifiSecurity::None => "Bez hesla",
+    }
+}
+
+fn contact_ui(ui: &mut egui::Ui, c: &mut ContactPayload, phones: &mut String, emails: &mut String) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label("Formát:");
+        let formats = [(ContactFormat::VCard3, "vCard 3.0"), (ContactFormat::VCard4, "vCard 4.0"), (ContactFormat::MeCard, "MeCard")];
+        for (f, label) in formats {
+            changed |= ui.selectable_value(&mut c.format, f, label).changed();
+        }
+    });
+    egui::Grid::new("contact_form").num_columns(2).show(ui, |ui| {
+        changed |= text_row(ui, "Jméno:", &mut c.first_name);
+        changed |= text_row(ui, "Příjmení:", &mut c.last_name);
+        changed |= text_row(ui, "Organizace:", &mut c.organization);
+        changed |= lines_row(ui, "Telefony (po řádcích):", phones);
+        changed |= lines_row(ui, "E-maily (po řádcích):", emails);
+        changed |= text_row(ui, "Ulice:", &mut c.street);
+        changed |= text_row(ui, "Město:", &mut c.city);
+        changed |= text_row(ui, "PSČ:", &mut c.postal_code);
+        changed |= text_row(ui, "Země:", &mut c.country);
+        changed |= text_row(ui, "Web:", &mut c.url);
+    });
+    changed
+}
+
+fn payment_ui(ui: &mut egui::Ui, p: &mut PaymentPayload, frame: &mut bool) -> bool {
+    let mut changed = false;
+    egui::Grid::new("payment_form").num_columns(2).show(ui, |ui| {
+        changed |= text_row(ui, "IBAN:", &mut p.iban);
+        changed |= text_row(ui, "Částka:", &mut p.amount);
+        changed |= text_row(ui, "Měna:", &mut p.currency);
+        changed |= text_row(ui, "Variabilní symbol:", &mut p.variable_symbol);
+        changed |= text_row(ui, "Konstantní symbol:", &mut p.constant_symbol);
+        changed |= text_row(ui, "Specifický symbol:", &mut p.specific_symbol);
+        changed |= text_row(ui, "Zpráva pro příjemce:", &mut p.message);
+        changed |= text_row(ui, "Splatnost (RRRR-MM-DD):", &mut p.due_date);
+
+        ui.label("");
+        changed |= ui.checkbox(frame, "Rámeček „QR Platba“ (doporučení ČBA)").changed();
+        ui.end_row();
+    });
+    changed
+}
+
+fn epc_ui(ui: &mut egui::Ui, p: &mut EpcPayload) -> bool {
+    let mut changed = false;
+    egui::Grid::new("epc_form").num_columns(2).show(ui, |ui| {
+        changed |= text_row(ui, "Příjemce:", &mut p.beneficiary);
+        changed |= text_row(ui, "IBAN:", &mut p.iban);
+        changed |= text_row(ui, "BIC (nepovinný):", &mut p.bic);
+        changed |= text_row(ui, "Částka (EUR):", &mut p.amount);
+        changed |= text_row(ui, "Kód účelu:", &mut p.purpose);
+        changed |= text_row(ui, "Reference (RF…):", &mut p.reference);
+        changed |= text_row(ui, "Zpráva (bez reference):", &mut p.text);
+    });
+    changed
+}
+
+fn event_ui(ui: &mut egui::Ui, e: &mut EventPayload) -> bool {
+    let mut changed = false;
+    egui::Grid::new("event_form").num_columns(2).show(ui, |ui| {
+        changed |= text_row(ui, "Název:", &mut e.title);
+        changed |= text_row(ui, "Začátek (D.M.RRRR HH:MM):", &mut e.start);
+        changed |= text_row(ui, "Konec:", &mut e.end);
+        changed |= text_row(ui, "Časové pásmo:", &mut e.time_zone);
+        changed |= text_row(ui, "Místo:", &mut e.location);
+        changed |= lines_row(ui, "Popis:", &mut e.description);
+    });
+    ui.weak("Bez času = celodenní akce; prázdné pásmo = čas telefonu, UTC nebo např. Europe/Prague.");
+    changed
+}
+
+/// Řádek mřížky: popisek + jednořádkové pole.
+fn text_row(ui: &mut egui::Ui, label: &str, value: &mut String) -> bool {
+    ui.label(label);
+    let changed = ui.add(TextEdit::singleline(value).desired_width(f32::INFINITY)).changed();
+    ui.end_row();
+    changed
+}
+
+/// Řádek mřížky: popisek + víceřádkové pole (hodnota na řádek).
+fn lines_row(ui: &mut egui::Ui, label: &str, value: &mut String) -> bool {
+    ui.label(label);
+    let changed = ui.add(TextEdit::multiline(value).desired_rows(2).desired_width(f32::INFINITY)).changed();
+    ui.end_row();
+    changed
+}
+
+fn lines(s: &str) -> Vec<String> {
+    s.lines().map(str::trim).filter(|l| !l.is_empty()).map(str::to_string).collect()
+}
@@ -0,0 +1,377 @@
+//! Export do PDF – vektorový QR i vložený rastrový obrázek.
+
+use crate::caption::{caption_height, render_caption, Caption, PaymentFrame, CAPTION_RATIO};
+use crate::error::Result;
+use crate::render::qr_matrix;
+use crate::shape::{eyes, module_shapes, PathCmd, Shape};
+use crate::style::{Gradient, GradientKind, GradientStop, Logo, QrStyle};
+use image::RgbaImage;
+use pdf_writer::types::FunctionShadingType;
+use pdf_writer::{Content, Filter, Finish, Name, Pdf, Ref};
+
+const MM_TO_PT: f32 = 72.0 / 25.4;
+
+/// Vykreslí QR kód jako jednostránkové PDF; strana stránky = `style.print_size_mm`.
+/// Moduly i pozadí jsou ve formuláři s transparentní skupinou, průhlednost se
+/// aplikuje na celou skupinu (stejně jako v rastru a SVG).
+/// Volitelný textový `caption` je pod symbolem jako rastr (300 dpi), rámeček QR Platby
+/// vektorově kolem symbolu; stránka se o ně zvětší.
+pub fn build_qr_pdf(url: &str, style: &QrStyle, logo: Option<&Logo>, caption: Option<Caption>) -> Result<Vec<u8>> {
+    let m = qr_matrix(url, style.ec_level, logo)?;
+    let qz = style.quiet_zone();
+    let page = style.print_size_mm.max(1.0) * MM_TO_PT; // strana QR
+    let frame = (caption == Some(Caption::PaymentFrame)).then(|| PaymentFrame::new(page));
+    let cap_h = if matches!(caption, Some(Caption::Text(_))) { page * CAPTION_RATIO } else { 0.0 };
+    let (page_w, page_h) = frame.as_ref().map_or((page, page + cap_h), |f| (f.width, f.height));
+    let (ox, oy) = frame.as_ref().map_or((0.0, 0.0), |f| f.qr_offset); // levý horní roh QR
+    let unit = page / (m.width + 2 * qz) as f32; // velikost modulu v pt
+
+    let catalog_id = Ref::new(1);
+    let page_tree_id = Ref::new(2);
+    let page_id = Ref::new(3);
+    let content_id = Ref::new(4);
+    let form_id = Ref::new(5);
+    let gs_id = Ref::new(6);
+    let logo_id = Ref::new(7);
+    let logo_mask_id = Ref::new(8);
+    let caption_id = Ref::new(9);
+    let caption_mask_id = Ref::new(10);
+    let shading_id = Ref::new(11);
+    let function_id = Ref::new(12); // u více zastávek skládaná funkce, části od 13
+
+    // obsah QR (PDF má počátek vlevo dole → y se převrací)
+    let mut form = Content::new();
+    if let Some(bg) = style.background_rgb {
+        set_fill(&mut form, bg);
+        form.rect(0.0, 0.0, page_w, page_h);
+        form.fill_nonzero();
+    }
+    set_fill(&mut form, style.module_rgb);
+    // útvary modulů: souřadnice v modulech → pt
+    let pt = |x: f32, y: f32| ((x + qz as f32) * unit + ox, page_h - oy - (y + qz as f32) * unit);
+    if style.gradient.is_some() {
+        form.save_state();
+    }
+    for shape in module_shapes(&m, style) {
+        pdf_path(&mut form, &shape, pt);
+    }
+    if style.gradient.is_some() {
+        // přechod: moduly jako ořez, plochu vyplní stínování
+        form.clip_nonzero();
+        form.end_path();
+        form.shading(Name(b"Grad"));
+        form.restore_state();
+    } else {
+        form.fill_nonzero();
+    }
+    if let Some(eye_style) = &style.eyes {
+        for eye in eyes(m.width, eye_style) {
+            set_fill(&mut form, eye_style.frame_rgb);
+            eye.frame.iter().for_each(|s| pdf_path(&mut form, s, pt));
+            form.fill_even_odd();
+            set_fill(&mut form, eye_style.pupil_rgb);
+            pdf_path(&mut form, &eye.pupil, pt);
+            form.fill_nonzero();
+        }
+    }
+
+    let logo_placed = logo.zip(m.logo_box).filter(|(l, _)| l.image.width() > 0 && l.image.height() > 0);
+    if let Some((l, (start, side))) = logo_placed {
+        let (lw, lh) = l.image.dimensions();
+        let box_pt = side as f32 * unit;
+        let scale = (box_pt / lw as f32).min(box_pt / lh as f32);
+        let (w, h) = (lw as f32 * scale, lh as f32 * scale);
+        let x = ox + (start + qz) as f32 * unit + (box_pt - w) / 2.0;
+        let y = page_h - oy - (start + qz) as f32 * unit - box_pt + (box_pt - h) / 2.0;
+        form.save_state();
+        form.transform([w, 0.0, 0.0, h, x, y]);
+        form.x_object(Name(b"Logo"));
+        form.restore_state();
+    }
+
+    if let Some(f) = &frame {
+        set_fill(&mut form, style.module_rgb);
+        pdf_cmds(&mut form, &f.outline, |x, y| (x, page_h - y));
+        form.fill_nonzero();
+    }
+
+    // popisek – text v barvě modulů na průhledném pozadí (pozadí už je ve formuláři)
+    let caption_img = match caption {
+        Some(Caption::Text(text)) => {
+            let w = (style.print_size_mm / 25.4 * 300.0).round().max(1.0) as u32;
+            Some(render_caption(text, w, caption_height(w), style.module_rgb, None, 255)?)
+        }
+        _ => None,
+    };
+    if caption_img.is_some() {
+        form.save_state();
+        form.transform([page, 0.0, 0.0, cap_h, 0.0, 0.0]);
+        form.x_object(Name(b"Caption"));
+        form.restore_state();
+    }
+    let form_data = deflate(&form.finish());
+
+    let mut pdf = Pdf::new();
+    pdf.catalog(catalog_id).pages(page_tree_id);
+    pdf.pages(page_tree_id).kids([page_id]).count(1);
+    let mut pg = pdf.page(page_id);
+    pg.media_box(pdf_writer::Rect::new(0.0, 0.0, page_w, page_h));
+    pg.parent(page_tree_id);
+    pg.contents(content_id);
+    {
+        let mut res = pg.resources();
+        res.x_objects().pair(Name(b"Qr"), form_id);
+        res.ext_g_states().pair(Name(b"Gs"), gs_id);
+    }
+    pg.finish();
+
+    pdf.ext_graphics(gs_id)
+        .non_stroking_alpha(style.alpha_percent.min(100) as f32 / 100.0);
+
+    let mut content = Content::new();
+    content.set_parameters(Name(b"Gs"));
+    content.x_object(Name(b"Qr"));
+    let content_data = deflate(&content.finish());
+    pdf.stream(content_id, &content_data).filter(Filter::FlateDecode);
+
+    let mut fx = pdf.form_xobject(form_id, &form_data);
+    fx.filter(Filter::FlateDecode);
+    fx.bbox(pdf_writer::Rect::new(0.0, 0.0, page_w, page_h));
+    fx.group().transparency().isolated(true);
+    if logo_placed.is_some() || caption_img.is_some() || style.gradient.is_some() {
+        let mut res = fx.resources();
+        if style.gradient.is_some() {
+            res.shadings().pair(Name(b"Grad"), shading_id);
+        }
+        if logo_placed.is_some() || caption_img.is_some() {
+            let mut xo = res.x_objects();
+            if logo_placed.is_some() {
+                xo.pair(Name(b"Logo"), logo_id);
+            }
+            if caption_img.is_some() {
+                xo.pair(Name(b"Caption"), caption_id);
+            }
+        }
+    }
+    fx.finish();
+
+    if let Some(g) = &style.gradient {
+        let ((ax, ay), (bx, by)) = g.geometry(m.width as f32);
+        let (a, b) = (pt(ax, ay), pt(bx, by));
+        let mut sh = pdf.function_shading(shading_id);
+        sh.color_space().device_rgb();
+        sh.function(function_id).extend([true, true]);
+        match g.kind {
+            GradientKind::Linear { .. } => {
+                sh.shading_type(FunctionShadingType::Axial).coords([a.0, a.1, b.0, b.1]);
+            }
+            GradientKind::Radial => {
+                sh.shading_type(FunctionShadingType::Radial).coords([a.0, a.1, 0.0, a.0, a.1, bx * unit]);
+            }
+        }
+        sh.finish();
+        write_gradient_function(&mut pdf, function_id, g);
+    }
+
+    if let Some((l, _)) = logo_placed {
+        write_pdf_image(&mut pdf, logo_id, logo_mask_id, &l.image);
+    }
+    if let Some(img) = &caption_img {
+        write_pdf_image(&mut pdf, caption_id, caption_mask_id, img);
+    }
+
+    Ok(pdf.finish())
+}
+
+/// Barvy přechodu jako PDF funkce 0–1 → RGB: dvě zastávky jedna lineární (exponenciální
+/// s n = 1) funkce, víc zastávek skládaná funkce z úseků s id za `id`.
+fn write_gradient_function(pdf: &mut Pdf, id: Ref, g: &Gradient) {
+    let mut stops = g.sorted_stops();
+    // PDF funkce pokrývá celé 0–1, krajní zastávky se prodlouží
+    let first = stops.first().map_or(GradientStop { offset: 0.0, rgb: (0, 0, 0) }, |s| *s);
+    let last = stops.last().map_or(first, |s| *s);
+    if first.offset > 0.0 || stops.is_empty() {
+        stops.insert(0, GradientStop { offset: 0.0, ..first });
+    }
+    if last.offset < 1.0 || stops.len() < 2 {
+        stops.push(GradientStop { offset: 1.0, ..last });
+    }
+    let rgb = |s: &GradientStop| [s.rgb.0, s.rgb.1, s.rgb.2].map(|c| c as f32 / 255.0);
+    let segment = |pdf: &mut Pdf, id: Ref, a: &GradientStop, b: &GradientStop| {
+        pdf.exponential_function(id).domain([0.0, 1.0]).c0(rgb(a)).c1(rgb(b)).n(1.0);
+    };
+    if stops.len() == 2 {
+        segment(pdf, id, &stops[0], &stops[1]);
+        return;
+    }
+    let parts: Vec<Ref> = (1..stops.len() as i32).map(|i| Ref::new(id.get() + i)).collect();
+    pdf.stitching_function(id)
+        .domain([0.0, 1.0])
+        .functions(parts.iter().copied())
+        .bounds(stops[1..stops.len() - 1].iter().map(|s| s.offset))
+        .encode(parts.iter().flat_map(|_| [0.0, 1.0]));
+    for (w, part) in stops.windows(2).zip(parts) {
+        segment(pdf, part, &w[0], &w[1]);
+    }
+}
+
+/// Vloží hotový (např. složený) obrázek do jednostránkového PDF.
+/// Velikost stránky = rozměry obrázku × `mm_per_px`.
+pub fn build_image_pdf(img: &RgbaImage, mm_per_px: f32) -> Vec<u8> {
+    let catalog_id = Ref::new(1);
+    let page_tree_id = Ref::new(2);
+    let page_id = Ref::new(3);
+    let content_id = Ref::new(4);
+    let image_id = Ref::new(5);
+    let mask_id = Ref::new(6);
+
+    let pt_per_px = mm_per_px.max(0.001) * MM_TO_PT;
+    let w = img.width() as f32 * pt_per_px;
+    let h = img.height() as f32 * pt_per_px;
+
+    let mut pdf = Pdf::new();
+    pdf.catalog(catalog_id).pages(page_tree_id);
+    pdf.pages(page_tree_id).kids([page_id]).count(1);
+    let mut pg = pdf.page(page_id);
+    pg.media_box(pdf_writer::Rect::new(0.0, 0.0, w, h));
+    pg.parent(page_tree_id);
+    pg.contents(content_id);
+    pg.resources().x_objects().pair(Name(b"Im"), image_id);
+    pg.finish();
+
+    let mut content = Content::new();
+    content.save_state();
+    content.transform([w, 0.0, 0.0, h, 0.0, 0.0]);
+    content.x_object(Name(b"Im"));
+    content.restore_state();
+    let content_data = deflate(&content.finish());
+    pdf.stream(content_id, &content_data).filter(Filter::FlateDecode);
+
+    write_pdf_image(&mut pdf, image_id, mask_id, img);
+    pdf.finish()
+}
+
+/// Zapíše RGBA obrázek jako image XObject (RGB + SMask s alfou, pokud je potřeba).
+fn write_pdf_image(pdf: &mut Pdf, id: Ref, mask_id: Ref, img: &RgbaImage) {
+    let rgb: Vec<u8> = img.pixels().flat_map(|p| [p[0], p[1], p[2]]).collect();
+    let has_alpha = img.pixels().any(|p| p[3] < 255);
+    let data = deflate(&rgb);
+
+    let mut xo = pdf.image_xobject(id, &data);
+    xo.filter(Filter::FlateDecode);
+    xo.width(img.width() as i32);
+    xo.height(img.height() as i32);
+    xo.color_space().device_rgb();
+    xo.bits_per_component(8);
+    if has_alpha {
+        xo.s_mask(mask_id);
+    }
+    xo.finish();
+
+    if has_alpha {
+        let alpha: Vec<u8> = img.pixels().map(|p| p[3]).collect();
+        let data = deflate(&alpha);
+        let mut mask = pdf.image_xobject(mask_id, &data);
+        mask.filter(Filter::FlateDecode);
+        mask.width(img.width() as i32);
+        mask.height(img.height() as i32);
+        mask.color_space().device_gray();
+        mask.bits_per_component(8);
+    }
+}
+
+/// Obrys útvaru do obsahu stránky; `pt` převádí souřadnice modulů na body PDF.
+fn pdf_path(c: &mut Content, shape: &Shape, pt: impl Fn(f32, f32) -> (f32, f32)) {
+    pdf_cmds(c, &shape.outline(), pt);
+}
+
+/// Příkazy obrysu do obsahu stránky; `pt` převádí souřadnice na body PDF.
+fn pdf_cmds(c: &mut Content, cmds: &[PathCmd], pt: impl Fn(f32, f32) -> (f32, f32)) {
+    for cmd in cmds {
+        match *cmd {
+            PathCmd::Move(x, y) => {
+                let (x, y) = pt(x, y);
+                c.move_to(x, y);
+            }
+            PathCmd::Line(x, y) => {
+                let (x, y) = pt(x, y);
+                c.line_to(x, y);
+            }
+            PathCmd::Cubic(k) => {
+                let ((x1, y1), (x2, y2), (x3, y3)) = (pt(k[0], k[1]), pt(k[2], k[3]), pt(k[4], k[5]));
+                c.cubic_to(x1, y1, x2, y2, x3, y3);
+            }
+            PathCmd::Close => {
+                c.close_path();
+            }
+        }
+    }
+}
+
+fn set_fill(c: &mut Content, rgb: (u8, u8, u8)) {
+    c.set_fill_rgb(rgb.0 as f32 / 255.0, rgb.1 as f32 / 255.0, rgb.2 as f32 / 255.0);
+}
+
+fn deflate(data: &[u8]) -> Vec<u8> {
+    miniz_oxide::deflate::compress_to_vec_zlib(data, 6)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    const URL: &str = "https://example.com";
+
+    fn count(pdf: &[u8], needle: &[u8]) -> usize {
+        pdf.windows(needle.len()).filter(|w| *w == needle).count()
+    }
+
+    #[test]
+    fn streams_are_deflated() {
+        let qr = build_qr_pdf(URL, &QrStyle::default(), None, None).unwrap();
+        assert_eq!(count(&qr, b"/FlateDecode"), 2); // obsah stránky a formulář s QR
+        let img = build_image_pdf(&RgbaImage::from_pixel(4, 2, Rgba([255, 0, 0, 255])), 0.5);
+        assert_eq!(count(&img, b"/FlateDecode"), 2); // obsah stránky a obrázek (bez alfy bez masky)
+        assert_eq!(count(&img, b"/Im Do"), 0);
+    }
+
+    #[cfg(feature = "verify")]
+    #[test]
+    fn page_matches_print_size() {
+        use hayro::hayro_syntax::Pdf;
+
+        let gradient = Gradient::even(GradientKind::Radial, &[(0, 0, 0), (0, 90, 0), (0, 0, 160)]);
+        let style = QrStyle { print_size_mm: 50.0, gradient: Some(gradient), ..QrStyle::default() };
+        let pdf = Pdf::new(build_qr_pdf(URL, &style, None, None).unwrap()).unwrap();
+        assert_eq!(pdf.pages().len(), 1);
+        let (w, h) = pdf.pages()[0].render_dimensions();
+        assert!((w - 50.0 * MM_TO_PT).abs() < 0.01 && (h - w).abs() < 0.01, "{w}×{h}");
+
+        let img = build_image_pdf(&RgbaImage::new(200, 100), 0.1); // 20 × 10 mm
+        let pdf = Pdf::new(img).unwrap();
+        let (w, h) = pdf.pages()[0].render_dimensions();
+        assert!((w - 20.0 * MM_TO_PT).abs() < 0.01 && (h - 10.0 * MM_TO_PT).abs() < 0.01, "{w}×{h}");
+    }
+
+    #[cfg(feature = "verify")]
+    #[test]
+    fn image_pdf_round_trips() {
+        // vlevo červená, vpravo modrá, levý dolní čtvrt průhledný (maska)
+        let img = RgbaImage::from_fn(40, 20, |x, y| match (x < 20, y < 10) {
+            (true, false) => Rgba([0, 0, 0, 0]),
+            (true, true) => Rgba([200, 0, 0, 255]),
+            (false, _) => Rgba([0, 0, 200, 255]),
+        });
+        let page = crate::verify::rasterize_pdf(&build_image_pdf(&img, 0.5), 80).unwrap();
+        assert_eq!(page.dimensions(), (80, 40));
+        let rgb = |x, y| {
+            let p = page.get_pixel(x, y);
+            [p[0], p[1], p[2]]
+        };
+        assert_eq!(rgb(10, 5), [200, 0, 0]);
+        assert_eq!(rgb(70, 30), [0, 0, 200]);
+        assert_eq!(rgb(10, 30), [255, 255, 255]);
+    }
+}
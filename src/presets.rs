@@ -0,0 +1,160 @@
+//! Pojmenované předvolby vzhledu QR – sdílení mezi uživateli přes JSON/TOML.
+
+use anyhow::Context;
+use kju_ar::{Corner, EyeStyle, Gradient, ModuleShape};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Vizuální parametry QR uložené pod jménem.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preset {
+    pub name: String,
+    pub size_px: u32,
+    pub module_rgb: (u8, u8, u8),
+    pub background_rgb: (u8, u8, u8),
+    pub alpha_percent: u8,
+    pub rounding_percent: u8,
+    pub quiet_zone_mod: u32,
+    pub module_shape: ModuleShape,
+    pub eyes: Option<EyeStyle>,
+    pub gradient: Option<Gradient>,
+    pub print_size_mm: f32,
+    pub dpi: Option<u32>, // Some => přesná tisková velikost i pro rastr
+    pub cut_background: bool,
+    pub corner: Corner,
+    pub offset_x: i32,
+    pub offset_y: i32,
+}
+
+impl Default for Preset {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            size_px: 160,
+            module_rgb: (0, 0, 0),
+            background_rgb: (255, 255, 255),
+            alpha_percent: 85,
+            rounding_percent: 0,
+            quiet_zone_mod: 4,
+            module_shape: ModuleShape::Square,
+            eyes: None,
+            gradient: None,
+            print_size_mm: 50.0,
+            dpi: None,
+            cut_background: true,
+            corner: Corner::Southeast,
+            offset_x: 10,
+            offset_y: 10,
+        }
+    }
+}
+
+/// Obsah souboru s předvolbami (TOML potřebuje tabulku na nejvyšší úrovni).
+#[derive(Default, Serialize, Deserialize)]
+struct PresetFile {
+    #[serde(default, rename = "preset")]
+    presets: Vec<Preset>,
+}
+
+/// Načte předvolby z `.json` nebo `.toml` (podle přípony).
+pub fn import(path: &Path) -> anyhow::Result<Vec<Preset>> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("Nejde číst soubor: {}", path.display()))?;
+    let file: PresetFile = if is_toml(path) {
+        toml::from_str(&text).with_context(|| format!("Neplatný TOML s předvolbami: {}", path.display()))?
+    } else {
+        serde_json::from_str(&text).with_context(|| format!("Neplatný JSON s předvolbami: {}", path.display()))?
+    };
+    let presets: Vec<Preset> = file.presets.into_iter().filter(|p| !p.name.trim().is_empty()).collect();
+    if presets.is_empty() {
+        anyhow::bail!("Soubor neobsahuje žádnou pojmenovanou předvolbu: {}", path.display());
+    }
+    Ok(presets)
+}
+
+/// Uloží předvolby do `.json` nebo `.toml` (podle přípony).
+pub fn export(path: &Path, presets: &[Preset]) -> anyhow::Result<()> {
+    let file = PresetFile { presets: presets.to_vec() };
+    let text = if is_toml(path) {
+        toml::to_string_pretty(&file).context("Předvolby nejde převést do TOML")?
+    } else {
+        serde_json::to_string_pretty(&file).context("Předvolby nejde převést do JSON")?
+    };
+    std::fs::write(path, text).with_context(|| format!("Zápis selhal: {}", path.display()))
+}
+
+/// Přidá předvolby; stejné jméno přepíše. Vrací počet přidaných/přepsaných.
+pub fn merge(into: &mut Vec<Preset>, new: Vec<Preset>) -> usize {
+    let n = new.len();
+    for p in new {
+        match into.iter_mut().find(|q| q.name == p.name) {
+            Some(q) => *q = p,
+            None => into.push(p),
+        }
+    }
+    n
+}
+
+fn is_toml(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()).is_some_and(|e| e.eq_ignore_ascii_case("toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kju_ar::{EyeShape, GradientKind};
+
+    fn full() -> Preset {
+        Preset {
+            name: "firemní".into(),
+            background_rgb: (250, 240, 200),
+            module_shape: ModuleShape::Dot,
+            eyes: Some(EyeStyle {
+                frame: EyeShape::Rounded,
+                pupil: EyeShape::Circle,
+                frame_rgb: (200, 0, 0),
+                pupil_rgb: (0, 0, 90),
+            }),
+            gradient: Some(Gradient::even(GradientKind::Linear { angle_deg: 45.0 }, &[(0, 0, 0), (0, 60, 140)])),
+            dpi: Some(300),
+            corner: Corner::Northwest,
+            ..Preset::default()
+        }
+    }
+
+    fn empty() -> Preset {
+        Preset { name: "prázdná".into(), ..Preset::default() }
+    }
+
+    #[test]
+    fn round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let radial = Gradient::even(GradientKind::Radial, &[(0, 0, 0), (90, 0, 0)]);
+        let radial = Preset { name: "kruh".into(), gradient: Some(radial), ..empty() };
+        let presets = vec![full(), empty(), radial];
+        for ext in ["json", "toml", "TOML"] {
+            let path = dir.path().join(format!("presets.{ext}"));
+            export(&path, &presets).unwrap();
+            assert_eq!(import(&path).unwrap(), presets, "{ext}");
+        }
+    }
+
+    #[test]
+    fn import_skips_unnamed_and_missing_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("presets.toml");
+        std::fs::write(&path, "[[preset]]\nname = \"jen jméno\"\n\n[[preset]]\nsize_px = 300\n").unwrap();
+        assert_eq!(import(&path).unwrap(), vec![Preset { name: "jen jméno".into(), ..Preset::default() }]);
+        std::fs::write(&path, "[[preset]]\nname = \" \"\n").unwrap();
+        assert!(import(&path).is_err());
+    }
+
+    #[test]
+    fn merge_overwrites_same_name() {
+        let mut presets = vec![full(), empty()];
+        let changed = Preset { size_px: 400, ..full() };
+        let other = Preset { name: "nová".into(), ..empty() };
+        assert_eq!(merge(&mut presets, vec![changed.clone(), other.clone()]), 2);
+        assert_eq!(presets, vec![changed, empty(), other]);
+    }
+}
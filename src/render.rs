@@ -0,0 +1,434 @@
+//! Vykreslení QR kódu – matice modulů, rastr a SVG.
+
+use crate::caption::{xml_escape, Caption, PaymentFrame, CAPTION_RATIO};
+use crate::error::{Error, Result};
+use crate::shape::{eyes, module_shapes, PathCmd, Shape};
+use crate::style::{logo_size_percent, min_ec_for_logo, Gradient, GradientKind, Logo, ModuleShape, QrStyle};
+use image::{imageops, DynamicImage, Rgb, RgbImage, Rgba, RgbaImage};
+use qrcode::{Color as QrColor, EcLevel, QrCode, Version};
+
+/// Matice modulů QR kódu připravená k vykreslení (rastr i vektor).
+pub(crate) struct QrMatrix {
+    pub(crate) width: u32,                   // šířka symbolu v modulech (bez tiché zóny)
+    pub(crate) dark: Vec<bool>,              // tmavé moduly po řádcích (už bez modulů vyčištěných pod logem)
+    pub(crate) logo_box: Option<(u32, u32)>, // (začátek, strana) čtverce pro logo v modulech
+}
+
+impl QrMatrix {
+    pub(crate) fn is_dark(&self, x: u32, y: u32) -> bool {
+        self.dark[(y * self.width + x) as usize]
+    }
+}
+
+/// Zakóduje text do matice modulů; s logem případně zvýší korekci a vyčistí místo pod ním.
+pub(crate) fn qr_matrix(url: &str, ec_level: EcLevel, logo: Option<&Logo>) -> Result<QrMatrix> {
+    let ec_level = match logo {
+        Some(l) => ec_level.max(min_ec_for_logo(l.size_percent, l.knockout)),
+        None => ec_level,
+    };
+    let code = QrCode::with_error_correction_level(url.as_bytes(), ec_level)?;
+    let width = code.width() as u32;
+    let mut dark: Vec<bool> = code.to_colors().into_iter().map(|c| c == QrColor::Dark).collect();
+
+    // oblast loga v modulech (vycentrovaná na mřížku, stejná parita jako šířka symbolu)
+    let logo_box = logo.map(|l| {
+        let mut side = ((width * logo_size_percent(l.size_percent) as u32) as f32 / 100.0).ceil() as u32;
+        if side % 2 != width % 2 {
+            side += 1;
+        }
+        let start = (width - side) / 2;
+        if l.knockout {
+            // vyčistit moduly pod logem + 1 modul okraj
+            let from = start.saturating_sub(1);
+            let to = (start + side + 1).min(width);
+            for y in from..to {
+                for x in from..to {
+                    dark[(y * width + x) as usize] = false;
+                }
+            }
+        }
+        (start, side)
+    });
+
+    Ok(QrMatrix { width, dark, logo_box })
+}
+
+/// Verze QR (1–40), kterou kodér zvolí pro text a úroveň korekce – čím vyšší, tím hustší kód.
+pub fn qr_version(text: &str, ec_level: EcLevel) -> Result<u8> {
+    match QrCode::with_error_correction_level(text.as_bytes(), ec_level)?.version() {
+        Version::Normal(v) | Version::Micro(v) => Ok(v as u8),
+    }
+}
+
+/// Vykreslí QR kód s barvou modulů, volitelnou barvou pozadí, průhledností a zaoblením.
+/// - `style.background_rgb = None` → pozadí QR je plně průhledné (ekvivalent „Odstranit pozadí“)
+/// - `style.gradient` přechod barvy modulů přes symbol (místo `module_rgb`)
+/// - `style.module_shape` tvar modulů; `style.rounding_percent` (0–50 % modulu) zaobluje čtverce
+/// - `style.ec_level` úroveň korekce chyb (L ≈ 7 %, M ≈ 15 %, Q ≈ 25 %, H ≈ 30 %)
+/// - `logo` volitelné logo uprostřed; korekce se podle něj případně zvýší
+pub fn build_qr_image(url: &str, style: &QrStyle, logo: Option<&Logo>) -> Result<RgbaImage> {
+    let m = qr_matrix(url, style.ec_level, logo)?;
+    let width_mod = m.width;
+    let quiet_zone_mod = style.quiet_zone();
+    let total_mod = width_mod + 2 * quiet_zone_mod;
+    let size_px = style.raster_px(width_mod);
+
+    // supersampling pro hladké zaoblení (u velkých rastrů méně – plátno nejvýš 8192 px)
+    let ss: u32 = (8192 / size_px.max(1)).clamp(1, 4);
+    let target_ss = size_px.max(total_mod) * ss;
+    let module_ss = (target_ss / total_mod).max(1);
+    let canvas_ss = module_ss * total_mod;
+
+    let a = ((style.alpha_percent as u16 * 255) / 100) as u8;
+    let mod_rgb = style.module_rgb;
+    let mod_rgba = Rgba([mod_rgb.0, mod_rgb.1, mod_rgb.2, a]);
+    let bg_rgba = match style.background_rgb {
+        Some(c) => Rgba([c.0, c.1, c.2, a]),
+        None => Rgba([0, 0, 0, 0]),
+    };
+
+    let mut img = RgbaImage::from_pixel(canvas_ss, canvas_ss, bg_rgba);
+
+    // přechod předpočítaný do 256 odstínů
+    let lut: Vec<Rgba<u8>> = match &style.gradient {
+        Some(g) => (0..=255u16)
+            .map(|i| {
+                let (r, gr, b) = g.color_at(i as f32 / 255.0);
+                Rgba([r, gr, b, a])
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+    let n = width_mod as f32;
+    let paint = |x: f32, y: f32| match &style.gradient {
+        Some(g) => lut[(g.position(x, y, n) * 255.0).round() as usize],
+        None => mod_rgba,
+    };
+
+    // vykresli moduly, případně oka v jejich barvách
+    for shape in module_shapes(&m, style) {
+        fill_area(&mut img, module_ss, quiet_zone_mod, shape.bounds(), |x, y| shape.contains(x, y), paint);
+    }
+    if let Some(eye_style) = &style.eyes {
+        let rgba = |c: (u8, u8, u8)| Rgba([c.0, c.1, c.2, a]);
+        let (frame_rgba, pupil_rgba) = (rgba(eye_style.frame_rgb), rgba(eye_style.pupil_rgb));
+        for eye in eyes(width_mod, eye_style) {
+            fill_area(&mut img, module_ss, quiet_zone_mod, eye.frame[0].bounds(), |x, y| eye.frame_contains(x, y), |_, _| frame_rgba);
+            fill_area(&mut img, module_ss, quiet_zone_mod, eye.pupil.bounds(), |x, y| eye.pupil.contains(x, y), |_, _| pupil_rgba);
+        }
+    }
+
+    // logo – zachová poměr stran, vycentruje se do vyhrazeného čtverce
+    if let (Some(l), Some((start, side))) = (logo, m.logo_box) {
+        let box_ss = side * module_ss;
+        let (lw, lh) = l.image.dimensions();
+        if lw > 0 && lh > 0 {
+            let scale = (box_ss as f32 / lw as f32).min(box_ss as f32 / lh as f32);
+            let nw = ((lw as f32 * scale).round() as u32).max(1);
+            let nh = ((lh as f32 * scale).round() as u32).max(1);
+            let mut scaled = imageops::resize(&l.image, nw, nh, imageops::FilterType::Lanczos3);
+            for p in scaled.pixels_mut() {
+                p[3] = ((p[3] as u16 * a as u16) / 255) as u8;
+            }
+            let ox = (start + quiet_zone_mod) * module_ss + box_ss.saturating_sub(nw) / 2;
+            let oy = (start + quiet_zone_mod) * module_ss + box_ss.saturating_sub(nh) / 2;
+            imageops::overlay(&mut img, &scaled, ox.into(), oy.into());
+        }
+    }
+
+    // downscale na cílovou velikost (vyhlazení hran)
+    let final_img = imageops::resize(&img, size_px, size_px, imageops::FilterType::Lanczos3);
+    Ok(final_img)
+}
+
+/// Obarví pixely, jejichž střed leží uvnitř; `inside` i `paint` dostávají souřadnice
+/// v modulech bez tiché zóny.
+fn fill_area(
+    img: &mut RgbaImage,
+    module_px: u32,
+    quiet_zone_mod: u32,
+    (x0, y0, x1, y1): (f32, f32, f32, f32),
+    inside: impl Fn(f32, f32) -> bool,
+    paint: impl Fn(f32, f32) -> Rgba<u8>,
+) {
+    let (ms, qz) = (module_px as f32, quiet_zone_mod as f32);
+    let px = |v: f32, max: u32| ((v + qz) * ms).clamp(0.0, max as f32);
+    for py in px(y0, img.height()).floor() as u32..px(y1, img.height()).ceil() as u32 {
+        for pxx in px(x0, img.width()).floor() as u32..px(x1, img.width()).ceil() as u32 {
+            let (x, y) = ((pxx as f32 + 0.5) / ms - qz, (py as f32 + 0.5) / ms - qz);
+            if inside(x, y) {
+                img.put_pixel(pxx, py, paint(x, y));
+            }
+        }
+    }
+}
+
+/// Skutečná strana rastru v px – `size_px`, nebo při `style.dpi` odvozená z fyzické velikosti.
+pub fn raster_size_px(url: &str, style: &QrStyle, logo: Option<&Logo>) -> Result<u32> {
+    Ok(style.raster_px(qr_matrix(url, style.ec_level, logo)?.width))
+}
+
+/// Vykreslí QR kód jako SVG (souřadnice v modulech, `size_px` jen jako výchozí rozměr;
+/// s `style.dpi` má dokument fyzický rozměr `print_size_mm`).
+/// Průhlednost se aplikuje na celou skupinu, takže moduly i pozadí vypadají stejně jako v rastru.
+/// Volitelný `caption` se vypíše pod symbol, nebo symbol obklopí rámeček QR Platby
+/// (stránka se o pruh či rámeček zvětší).
+pub fn build_qr_svg(url: &str, style: &QrStyle, logo: Option<&Logo>, caption: Option<Caption>) -> Result<String> {
+    use std::fmt::Write as _;
+
+    let m = qr_matrix(url, style.ec_level, logo)?;
+    let qz = style.quiet_zone();
+    let total = m.width + 2 * qz;
+
+    let frame = (caption == Some(Caption::PaymentFrame)).then(|| PaymentFrame::new(total as f32));
+    let cap = if matches!(caption, Some(Caption::Text(_))) { total as f32 * CAPTION_RATIO } else { 0.0 };
+    let (width, height) = frame.as_ref().map_or((total as f32, total as f32 + cap), |f| (f.width, f.height));
+
+    let mut svg = String::new();
+    let _ = writeln!(svg, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    let scale = |v: f32| v / total as f32;
+    let (w, h) = match style.dpi {
+        Some(_) => {
+            let mm = style.print_size_mm;
+            (format!("{}mm", svg_num(mm * scale(width))), format!("{}mm", svg_num(mm * scale(height))))
+        }
+        None => {
+            let px = style.size_px as f32;
+            ((px * scale(width)).round().to_string(), (px * scale(height)).round().to_string())
+        }
+    };
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {} {}">"#,
+        svg_num(width),
+        svg_num(height),
+    );
+    let _ = writeln!(svg, r#"<g opacity="{}">"#, style.alpha_percent.min(100) as f32 / 100.0);
+    if let Some(bg) = style.background_rgb {
+        let _ = writeln!(svg, r#"<rect width="{}" height="{}" fill="{}"/>"#, svg_num(width), svg_num(height), svg_color(bg));
+    }
+    if let Some(f) = &frame {
+        let (x, y) = f.qr_offset;
+        let _ = writeln!(svg, r#"<g transform="translate({} {})">"#, svg_num(x), svg_num(y));
+    }
+
+    let fill = match &style.gradient {
+        Some(g) => {
+            svg_gradient(&mut svg, g, m.width, qz);
+            "url(#qr-gradient)".to_string()
+        }
+        None => svg_color(style.module_rgb),
+    };
+    let mut d = String::new();
+    for shape in module_shapes(&m, style) {
+        svg_path(&mut d, &shape, qz);
+    }
+    // čtvercové moduly bez vyhlazení hran (ostré přechody mezi sousedy)
+    let crisp = if style.module_shape == ModuleShape::Square && style.rounding_percent == 0 {
+        r#" shape-rendering="crispEdges""#
+    } else {
+        ""
+    };
+    let _ = writeln!(svg, r#"<path fill="{fill}"{crisp} d="{d}"/>"#);
+    if let Some(eye_style) = &style.eyes {
+        for eye in eyes(m.width, eye_style) {
+            let (mut frame, mut pupil) = (String::new(), String::new());
+            eye.frame.iter().for_each(|s| svg_path(&mut frame, s, qz));
+            svg_path(&mut pupil, &eye.pupil, qz);
+            let (fc, pc) = (svg_color(eye_style.frame_rgb), svg_color(eye_style.pupil_rgb));
+            let _ = writeln!(svg, r#"<path fill="{fc}" fill-rule="evenodd" d="{frame}"/>"#);
+            let _ = writeln!(svg, r#"<path fill="{pc}" d="{pupil}"/>"#);
+        }
+    }
+
+    // logo jako vložené PNG (data URI)
+    if let (Some(l), Some((start, side))) = (logo, m.logo_box) {
+        use base64::Engine as _;
+        let mut png = Vec::new();
+        DynamicImage::ImageRgba8(l.image.clone())
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageOutputFormat::Png)
+            .map_err(Error::EncodeLogo)?;
+        let b64 = base64::engine::general_purpose::STANDARD.encode(&png);
+        let _ = writeln!(
+            svg,
+            r#"<image x="{pos}" y="{pos}" width="{side}" height="{side}" preserveAspectRatio="xMidYMid meet" href="data:image/png;base64,{b64}"/>"#,
+            pos = start + qz,
+        );
+    }
+
+    if let Some(f) = &frame {
+        let mut d = String::new();
+        svg_cmds(&mut d, &f.outline, 0.0);
+        let _ = writeln!(svg, "</g>");
+        let _ = writeln!(svg, r#"<path fill="{}" d="{d}"/>"#, svg_color(style.module_rgb));
+    }
+
+    if let Some(Caption::Text(text)) = caption {
+        // velikost písma odhadem (průměrná šířka znaku ~0,55 em), aby se text vešel
+        let chars = text.chars().count().max(1) as f32;
+        let size = (cap * 0.7).min(total as f32 * 0.92 / (chars * 0.55));
+        let _ = writeln!(
+            svg,
+            r#"<text x="{x}" y="{y}" font-family="Segoe UI, Arial, DejaVu Sans, sans-serif" font-size="{size}" text-anchor="middle" dominant-baseline="central" fill="{fill}">{t}</text>"#,
+            x = total as f32 / 2.0,
+            y = total as f32 + cap / 2.0,
+            t = xml_escape(text),
+        );
+    }
+
+    let _ = writeln!(svg, "</g>");
+    let _ = writeln!(svg, "</svg>");
+    Ok(svg)
+}
+
+/// Definice přechodu `#qr-gradient` v souřadnicích stránky (symbol je posunutý o tichou zónu).
+fn svg_gradient(svg: &mut String, g: &Gradient, width: u32, qz: u32) {
+    use std::fmt::Write as _;
+
+    let q = qz as f32;
+    let ((ax, ay), (bx, by)) = g.geometry(width as f32);
+    let _ = writeln!(svg, "<defs>");
+    let _ = match g.kind {
+        GradientKind::Linear { .. } => writeln!(
+            svg,
+            r#"<linearGradient id="qr-gradient" gradientUnits="userSpaceOnUse" x1="{}" y1="{}" x2="{}" y2="{}">"#,
+            svg_num(ax + q),
+            svg_num(ay + q),
+            svg_num(bx + q),
+            svg_num(by + q),
+        ),
+        GradientKind::Radial => writeln!(
+            svg,
+            r#"<radialGradient id="qr-gradient" gradientUnits="userSpaceOnUse" cx="{}" cy="{}" r="{}">"#,
+            svg_num(ax + q),
+            svg_num(ay + q),
+            svg_num(bx),
+        ),
+    };
+    for stop in g.sorted_stops() {
+        let _ = writeln!(svg, r#"<stop offset="{}" stop-color="{}"/>"#, svg_num(stop.offset), svg_color(stop.rgb));
+    }
+    let tag = match g.kind {
+        GradientKind::Linear { .. } => "linearGradient",
+        GradientKind::Radial => "radialGradient",
+    };
+    let _ = writeln!(svg, "</{tag}>\n</defs>");
+}
+
+/// Připojí obrys útvaru do atributu `d` (posunutý o tichou zónu).
+fn svg_path(d: &mut String, shape: &Shape, quiet_zone_mod: u32) {
+    use std::fmt::Write as _;
+
+    let qz = quiet_zone_mod as f32;
+    if let Shape::Rect { x, y, w, h, radii } = shape
+        && radii.iter().all(|r| *r <= 0.0)
+    {
+        // ostrý obdélník (běh čtvercových modulů) – krátký zápis
+        let (w, h) = (svg_num(*w), svg_num(*h));
+        let _ = write!(d, "M{} {}h{w}v{h}h-{w}z", svg_num(x + qz), svg_num(y + qz));
+        return;
+    }
+    svg_cmds(d, &shape.outline(), qz);
+}
+
+/// Připojí příkazy obrysu do atributu `d`, body posunuté o `offset` v obou osách.
+fn svg_cmds(d: &mut String, cmds: &[PathCmd], offset: f32) {
+    use std::fmt::Write as _;
+
+    let p = |x: f32, y: f32| format!("{} {}", svg_num(x + offset), svg_num(y + offset));
+    for cmd in cmds {
+        let _ = match cmd {
+            PathCmd::Move(x, y) => write!(d, "M{}", p(*x, *y)),
+            PathCmd::Line(x, y) => write!(d, "L{}", p(*x, *y)),
+            PathCmd::Cubic(c) => write!(d, "C{} {} {}", p(c[0], c[1]), p(c[2], c[3]), p(c[4], c[5])),
+            PathCmd::Close => write!(d, "z"),
+        };
+    }
+}
+
+/// Souřadnice do SVG – nejvýš 3 desetinná místa, bez zbytečných nul.
+fn svg_num(v: f32) -> String {
+    let s = format!("{v:.3}");
+    s.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+fn svg_color(c: (u8, u8, u8)) -> String {
+    format!("#{:02x}{:02x}{:02x}", c.0, c.1, c.2)
+}
+
+/// Slije RGBA na zadané RGB pozadí (pro JPEG).
+pub fn flatten_rgba_to_rgb(src: &RgbaImage, bg: (u8, u8, u8)) -> RgbImage {
+    let (w, h) = src.dimensions();
+    let mut dst = RgbImage::new(w, h);
+    for (x, y, p) in src.enumerate_pixels() {
+        let (sr, sg, sb, sa) = (p[0] as u16, p[1] as u16, p[2] as u16, p[3] as u16);
+        let a = sa; // 0..255
+        let ir = (sr * a + (bg.0 as u16) * (255 - a) + 127) / 255;
+        let ig = (sg * a + (bg.1 as u16) * (255 - a) + 127) / 255;
+        let ib = (sb * a + (bg.2 as u16) * (255 - a) + 127) / 255;
+        dst.put_pixel(x, y, Rgb([ir as u8, ig as u8, ib as u8]));
+    }
+    dst
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URL: &str = "https://example.com";
+
+    /// Hodnota prvního atributu `name` v SVG.
+    fn attr<'a>(svg: &'a str, name: &str) -> &'a str {
+        let start = svg.find(&format!(" {name}=\"")).expect(name) + name.len() + 3;
+        &svg[start..start + svg[start..].find('"').unwrap()]
+    }
+
+    #[test]
+    fn svg_view_box_includes_quiet_zone() {
+        let width = qr_matrix(URL, EcLevel::M, None).unwrap().width;
+        for qz in [0, 2, 4, 20] {
+            let style = QrStyle { quiet_zone_mod: qz, size_px: 300, ..QrStyle::default() };
+            let svg = build_qr_svg(URL, &style, None, None).unwrap();
+            let side = width + 2 * qz.min(10); // tichá zóna je omezená na 10 modulů
+            assert_eq!(attr(&svg, "viewBox"), format!("0 0 {side} {side}"), "{qz}");
+            assert_eq!((attr(&svg, "width"), attr(&svg, "height")), ("300", "300"), "{qz}");
+        }
+        // s DPI tisková velikost v mm
+        let style = QrStyle { dpi: Some(300), print_size_mm: 25.0, ..QrStyle::default() };
+        let svg = build_qr_svg(URL, &style, None, None).unwrap();
+        assert_eq!((attr(&svg, "width"), attr(&svg, "height")), ("25mm", "25mm"));
+    }
+
+    #[test]
+    fn svg_gradient_defs() {
+        let colors = [(0, 0, 0), (0, 90, 0), (0, 0, 160)];
+        for (kind, tag) in
+            [(GradientKind::Linear { angle_deg: 90.0 }, "linearGradient"), (GradientKind::Radial, "radialGradient")]
+        {
+            let style = QrStyle { gradient: Some(Gradient::even(kind, &colors)), ..QrStyle::default() };
+            let svg = build_qr_svg(URL, &style, None, None).unwrap();
+            assert!(svg.contains(&format!("<defs>\n<{tag} id=\"qr-gradient\"")), "{tag}");
+            assert_eq!(svg.matches("<stop ").count(), 3, "{tag}");
+            assert!(svg.contains(r#"<path fill="url(#qr-gradient)""#), "{tag}");
+        }
+        let plain = build_qr_svg(URL, &QrStyle::default(), None, None).unwrap();
+        assert!(!plain.contains("<defs>") && plain.contains(r##"<path fill="#000000""##));
+    }
+
+    #[cfg(feature = "verify")]
+    #[test]
+    fn svg_rasterizes_at_declared_size() {
+        let gradient = Gradient::even(GradientKind::Linear { angle_deg: 0.0 }, &[(0, 0, 0), (0, 0, 160)]);
+        let style = QrStyle { size_px: 240, alpha_percent: 100, gradient: Some(gradient), ..QrStyle::default() };
+        let svg = build_qr_svg(URL, &style, None, None).unwrap();
+        let img = crate::verify::rasterize_svg(svg.as_bytes(), 240).unwrap();
+        assert_eq!(img.dimensions(), (240, 240));
+
+        // tichá zóna bílá, střed levé horní rohové značky tmavý (začátek přechodu)
+        let total = (qr_matrix(URL, style.ec_level, None).unwrap().width + 2 * style.quiet_zone()) as f32;
+        let px = |modules: f32| (modules * 240.0 / total) as u32;
+        assert_eq!(img.get_pixel(1, 1).0, [255, 255, 255, 255]);
+        let eye = img.get_pixel(px(style.quiet_zone() as f32 + 3.5), px(style.quiet_zone() as f32 + 3.5));
+        assert!(eye[0] < 30 && eye[2] < 60, "{eye:?}");
+    }
+}
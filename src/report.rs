@@ -0,0 +1,74 @@
+//! Výsledek hromadného exportu po položkách a jeho uložení do CSV.
+
+use crate::error::{Error, Result};
+use std::path::{Path, PathBuf};
+
+/// Výsledek jedné položky hromadného exportu.
+#[derive(Clone, Debug)]
+pub struct BulkOutcome {
+    pub index1: usize, // pořadí položky (od 1)
+    pub payload: String,
+    pub path: PathBuf,
+    pub error: Option<String>,      // None => uloženo
+    pub unreadable: Option<String>, // uložený QR nejde přečíst (jen s ověřením)
+}
+
+/// Průběh hromadného exportu – hlásí se před každou položkou.
+#[derive(Clone, Debug)]
+pub struct BulkProgress {
+    pub done: usize, // hotové položky (uložené i chybné)
+    pub total: usize,
+    pub payload: String, // právě zpracovávaná položka
+}
+
+/// Souhrn hromadného exportu – jedna chybná položka nezastaví ostatní.
+#[derive(Clone, Debug, Default)]
+pub struct BulkReport {
+    pub dir: PathBuf,
+    pub outcomes: Vec<BulkOutcome>,
+    pub total: usize,    // počet položek na vstupu
+    pub cancelled: bool, // přerušeno uživatelem – `outcomes` jsou jen zpracované položky
+}
+
+impl BulkReport {
+    pub fn ok_count(&self) -> usize {
+        self.outcomes.iter().filter(|o| o.error.is_none()).count()
+    }
+
+    pub fn failed_count(&self) -> usize {
+        self.outcomes.len() - self.ok_count()
+    }
+
+    /// Uložené soubory, které ověření nepřečetlo.
+    pub fn unreadable_count(&self) -> usize {
+        self.outcomes.iter().filter(|o| o.unreadable.is_some()).count()
+    }
+
+    /// Položky, na které po přerušení nedošlo.
+    pub fn skipped_count(&self) -> usize {
+        self.total.saturating_sub(self.outcomes.len())
+    }
+
+    /// Cesta k poslednímu uloženému souboru.
+    pub fn last_saved(&self) -> Option<&Path> {
+        self.outcomes.iter().rev().find(|o| o.error.is_none()).map(|o| o.path.as_path())
+    }
+
+    /// Uloží report jako CSV (pořadí, obsah, soubor, výsledek, důvod).
+    pub fn write_csv(&self, path: &Path) -> Result<()> {
+        let csv_err = |source: csv::Error| Error::WriteTable { path: path.to_path_buf(), source };
+        let mut w = csv::Writer::from_path(path).map_err(csv_err)?;
+        w.write_record(["pořadí", "obsah", "soubor", "výsledek", "důvod"]).map_err(csv_err)?;
+        for o in &self.outcomes {
+            let index = o.index1.to_string();
+            let file = o.path.to_string_lossy();
+            let (state, reason) = match (&o.error, &o.unreadable) {
+                (Some(e), _) => ("chyba", e.as_str()),
+                (None, Some(e)) => ("nečitelný", e.as_str()),
+                (None, None) => ("OK", ""),
+            };
+            w.write_record([index.as_str(), &o.payload, &file, state, reason]).map_err(csv_err)?;
+        }
+        w.flush().map_err(Error::io(path))
+    }
+}
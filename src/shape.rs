@@ -0,0 +1,262 @@
+//! Tvary modulů – geometrie v souřadnicích modulů, společná pro rastr, SVG i PDF.
+
+use crate::render::QrMatrix;
+use crate::style::{EyeShape, EyeStyle, ModuleShape, QrStyle};
+
+/// Aproximace čtvrtkruhu Bézierovou křivkou.
+pub(crate) const KAPPA: f32 = 0.552_284_8;
+
+/// Útvar v souřadnicích modulů bez tiché zóny (y roste dolů).
+pub(crate) enum Shape {
+    /// Obdélník; poloměry rohů v pořadí levý-horní, pravý-horní, pravý-dolní, levý-dolní.
+    Rect { x: f32, y: f32, w: f32, h: f32, radii: [f32; 4] },
+    /// Konvexní mnohoúhelník (kosočtverec).
+    Polygon(Vec<(f32, f32)>),
+}
+
+/// Příkaz obrysu; křivky jsou kubické Bézierovy (SVG i PDF je umí přímo).
+pub(crate) enum PathCmd {
+    Move(f32, f32),
+    Line(f32, f32),
+    Cubic([f32; 6]),
+    Close,
+}
+
+impl PathCmd {
+    /// Stejný příkaz s body převedenými funkcí `f`.
+    pub(crate) fn map(&self, f: impl Fn(f32, f32) -> (f32, f32)) -> PathCmd {
+        match *self {
+            PathCmd::Move(x, y) => {
+                let (x, y) = f(x, y);
+                PathCmd::Move(x, y)
+            }
+            PathCmd::Line(x, y) => {
+                let (x, y) = f(x, y);
+                PathCmd::Line(x, y)
+            }
+            PathCmd::Cubic([x1, y1, x2, y2, x, y]) => {
+                let ((x1, y1), (x2, y2), (x, y)) = (f(x1, y1), f(x2, y2), f(x, y));
+                PathCmd::Cubic([x1, y1, x2, y2, x, y])
+            }
+            PathCmd::Close => PathCmd::Close,
+        }
+    }
+}
+
+impl Shape {
+    fn square(x: u32, y: u32, radii: [f32; 4]) -> Self {
+        Shape::Rect { x: x as f32, y: y as f32, w: 1.0, h: 1.0, radii }
+    }
+
+    /// Obrys po směru hodinových ručiček (při y dolů) – překryvy se sjednotí i s nonzero.
+    pub(crate) fn outline(&self) -> Vec<PathCmd> {
+        match self {
+            Shape::Rect { x, y, w, h, radii: [r0, r1, r2, r3] } => {
+                let (x, y, x1, y1) = (*x, *y, x + w, y + h);
+                let mut p = vec![PathCmd::Move(x + r0, y), PathCmd::Line(x1 - r1, y)];
+                if *r1 > 0.0 {
+                    let k = r1 * KAPPA;
+                    p.push(PathCmd::Cubic([x1 - r1 + k, y, x1, y + r1 - k, x1, y + r1]));
+                }
+                p.push(PathCmd::Line(x1, y1 - r2));
+                if *r2 > 0.0 {
+                    let k = r2 * KAPPA;
+                    p.push(PathCmd::Cubic([x1, y1 - r2 + k, x1 - r2 + k, y1, x1 - r2, y1]));
+                }
+                p.push(PathCmd::Line(x + r3, y1));
+                if *r3 > 0.0 {
+                    let k = r3 * KAPPA;
+                    p.push(PathCmd::Cubic([x + r3 - k, y1, x, y1 - r3 + k, x, y1 - r3]));
+                }
+                p.push(PathCmd::Line(x, y + r0));
+                if *r0 > 0.0 {
+                    let k = r0 * KAPPA;
+                    p.push(PathCmd::Cubic([x, y + r0 - k, x + r0 - k, y, x + r0, y]));
+                }
+                p.push(PathCmd::Close);
+                p
+            }
+            Shape::Polygon(pts) => {
+                let mut p: Vec<PathCmd> = pts.iter().map(|&(x, y)| PathCmd::Line(x, y)).collect();
+                if let Some(PathCmd::Line(x, y)) = p.first() {
+                    p[0] = PathCmd::Move(*x, *y);
+                }
+                p.push(PathCmd::Close);
+                p
+            }
+        }
+    }
+
+    /// Ohraničení (x0, y0, x1, y1).
+    pub(crate) fn bounds(&self) -> (f32, f32, f32, f32) {
+        match self {
+            Shape::Rect { x, y, w, h, .. } => (*x, *y, x + w, y + h),
+            Shape::Polygon(pts) => pts.iter().fold((f32::MAX, f32::MAX, f32::MIN, f32::MIN), |b, &(x, y)| {
+                (b.0.min(x), b.1.min(y), b.2.max(x), b.3.max(y))
+            }),
+        }
+    }
+
+    /// Leží bod uvnitř útvaru? (pro rastr – testuje se střed pixelu)
+    pub(crate) fn contains(&self, px: f32, py: f32) -> bool {
+        match self {
+            Shape::Rect { x, y, w, h, radii } => {
+                let (x1, y1) = (x + w, y + h);
+                if px < *x || py < *y || px >= x1 || py >= y1 {
+                    return false;
+                }
+                // střed zaoblení každého rohu; bod v rohovém čtverci musí být v kruhu
+                let corners = [
+                    (x + radii[0], y + radii[0]),
+                    (x1 - radii[1], y + radii[1]),
+                    (x1 - radii[2], y1 - radii[2]),
+                    (x + radii[3], y1 - radii[3]),
+                ];
+                corners.iter().zip(radii).enumerate().all(|(i, (&(cx, cy), &r))| {
+                    let outside_x = if i == 0 || i == 3 { px < cx } else { px > cx };
+                    let outside_y = if i < 2 { py < cy } else { py > cy };
+                    r <= 0.0 || !(outside_x && outside_y) || (px - cx).powi(2) + (py - cy).powi(2) <= r * r
+                })
+            }
+            Shape::Polygon(pts) => {
+                // konvexní a po směru hodinových ručiček → bod je vpravo od všech hran
+                pts.iter().zip(pts.iter().cycle().skip(1)).all(|(&(ax, ay), &(bx, by))| {
+                    (bx - ax) * (py - ay) - (by - ay) * (px - ax) >= 0.0
+                })
+            }
+        }
+    }
+}
+
+/// Útvary všech tmavých modulů podle `style.module_shape`; s vlastními oky (`style.eyes`)
+/// bez tří hledacích vzorů. Bez vlastních ok se hledací vzory u jiných tvarů než čtverce
+/// kreslí plné – tečky či pruhy by rozbily poměr 1:1:3:1:1, podle kterého je čtečky hledají.
+pub(crate) fn module_shapes(m: &QrMatrix, style: &QrStyle) -> Vec<Shape> {
+    let n = m.width;
+    let solid_finders = style.eyes.is_none() && style.module_shape != ModuleShape::Square;
+    let skip_finders = style.eyes.is_some() || solid_finders;
+    let on = |x: u32, y: u32| m.is_dark(x, y) && !(skip_finders && in_finder(n, x, y));
+    let dark = |x: i64, y: i64| x >= 0 && y >= 0 && x < n as i64 && y < n as i64 && on(x as u32, y as u32);
+    let dark_modules = || (0..n).flat_map(move |y| (0..n).map(move |x| (x, y))).filter(move |&(x, y)| on(x, y));
+    let mut shapes = Vec::new();
+    if solid_finders {
+        shapes.extend(runs(n, |x, y| m.is_dark(x, y) && in_finder(n, x, y)));
+    }
+    match style.module_shape {
+        ModuleShape::Square if style.rounding_percent == 0 => shapes.extend(runs(n, on)),
+        ModuleShape::Square => {
+            let r = style.rounding_percent.min(50) as f32 / 100.0;
+            shapes.extend(dark_modules().map(|(x, y)| Shape::square(x, y, [r; 4])));
+        }
+        ModuleShape::Dot => {
+            const D: f32 = 0.9; // mezera mezi tečkami
+            let off = (1.0 - D) / 2.0;
+            shapes.extend(dark_modules().map(|(x, y)| Shape::Rect {
+                x: x as f32 + off,
+                y: y as f32 + off,
+                w: D,
+                h: D,
+                radii: [D / 2.0; 4],
+            }));
+        }
+        ModuleShape::Diamond => {
+            shapes.extend(dark_modules().map(|(x, y)| {
+                let (x, y) = (x as f32, y as f32);
+                Shape::Polygon(vec![(x + 0.5, y), (x + 1.0, y + 0.5), (x + 0.5, y + 1.0), (x, y + 0.5)])
+            }));
+        }
+        ModuleShape::VerticalBars | ModuleShape::HorizontalBars => {
+            const W: f32 = 0.8; // šířka pruhu
+            let vertical = style.module_shape == ModuleShape::VerticalBars;
+            for line in 0..n {
+                let at = |i: u32| if vertical { on(line, i) } else { on(i, line) };
+                let mut i = 0;
+                while i < n {
+                    if !at(i) {
+                        i += 1;
+                        continue;
+                    }
+                    let start = i;
+                    while i < n && at(i) {
+                        i += 1;
+                    }
+                    let (along, len) = (start as f32 + (1.0 - W) / 2.0, (i - start) as f32 - (1.0 - W));
+                    let across = line as f32 + (1.0 - W) / 2.0;
+                    let (x, y, w, h) = if vertical { (across, along, W, len) } else { (along, across, len, W) };
+                    shapes.push(Shape::Rect { x, y, w, h, radii: [W / 2.0; 4] });
+                }
+            }
+        }
+        ModuleShape::Liquid => {
+            // roh se zaoblí, jen když jsou oba sousedé u něj světlí – spojené moduly navazují rovně
+            shapes.extend(dark_modules().map(|(x, y)| {
+                let (xi, yi) = (x as i64, y as i64);
+                let (up, right, down, left) = (dark(xi, yi - 1), dark(xi + 1, yi), dark(xi, yi + 1), dark(xi - 1, yi));
+                let r = |a: bool, b: bool| if a || b { 0.0 } else { 0.5 };
+                Shape::square(x, y, [r(up, left), r(up, right), r(down, right), r(down, left)])
+            }));
+        }
+    }
+    shapes
+}
+
+/// Vodorovné běhy modulů, pro které platí `on` – čtverce bez švů mezi sousedy.
+fn runs(n: u32, on: impl Fn(u32, u32) -> bool) -> Vec<Shape> {
+    let mut shapes = Vec::new();
+    for y in 0..n {
+        let mut x = 0;
+        while x < n {
+            if !on(x, y) {
+                x += 1;
+                continue;
+            }
+            let start = x;
+            while x < n && on(x, y) {
+                x += 1;
+            }
+            shapes.push(Shape::Rect { x: start as f32, y: y as f32, w: (x - start) as f32, h: 1.0, radii: [0.0; 4] });
+        }
+    }
+    shapes
+}
+
+/// Leží modul v některém ze tří hledacích vzorů 7×7?
+fn in_finder(n: u32, x: u32, y: u32) -> bool {
+    let (near, far) = (|v: u32| v < 7, |v: u32| v + 7 >= n);
+    (near(x) && near(y)) || (far(x) && near(y)) || (near(x) && far(y))
+}
+
+/// Oko (hledací vzor) – rámeček jako vnější obrys s otvorem (vyplňovat even-odd) a zornice.
+pub(crate) struct Eye {
+    pub(crate) frame: [Shape; 2],
+    pub(crate) pupil: Shape,
+}
+
+impl Eye {
+    /// Bod v rámečku (mezi vnějším obrysem a otvorem)?
+    pub(crate) fn frame_contains(&self, px: f32, py: f32) -> bool {
+        self.frame[0].contains(px, py) && !self.frame[1].contains(px, py)
+    }
+}
+
+/// Tři oka symbolu o šířce `n` modulů (vlevo nahoře, vpravo nahoře, vlevo dole).
+pub(crate) fn eyes(n: u32, style: &EyeStyle) -> [Eye; 3] {
+    // „list“ je u pravého horního a levého dolního oka zrcadlený, aby byly souměrné
+    [(0, 0, false), (n - 7, 0, true), (0, n - 7, true)].map(|(x, y, mirror)| {
+        let (x, y) = (x as f32, y as f32);
+        let rect = |off: f32, size: f32, shape: EyeShape| {
+            let r = match shape {
+                EyeShape::Square => [0.0; 4],
+                EyeShape::Rounded => [size * 2.0 / 7.0; 4],
+                EyeShape::Circle => [size / 2.0; 4],
+                EyeShape::Leaf if mirror => [0.0, size * 3.0 / 7.0, 0.0, size * 3.0 / 7.0],
+                EyeShape::Leaf => [size * 3.0 / 7.0, 0.0, size * 3.0 / 7.0, 0.0],
+            };
+            Shape::Rect { x: x + off, y: y + off, w: size, h: size, radii: r }
+        };
+        Eye {
+            frame: [rect(0.0, 7.0, style.frame), rect(1.0, 5.0, style.frame)],
+            pupil: rect(2.0, 3.0, style.pupil),
+        }
+    })
+}
@@ -0,0 +1,461 @@
+//! Vzhled QR kódu, logo a výstupní formáty.
+
+use crate::error::{Error, Result};
+use image::RgbaImage;
+use qrcode::EcLevel;
+use std::path::Path;
+
+/// Vzhled samostatného QR kódu.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct QrStyle {
+    pub size_px: u32,
+    pub module_rgb: (u8, u8, u8),
+    pub gradient: Option<Gradient>,           // Some => přechod místo jednolité `module_rgb`
+    pub background_rgb: Option<(u8, u8, u8)>, // None => průhledné pozadí
+    pub alpha_percent: u8,                    // 0–100 %
+    pub rounding_percent: u8,                 // 0–50 % z velikosti modulu (jen `ModuleShape::Square`)
+    pub module_shape: ModuleShape,
+    pub eyes: Option<EyeStyle>, // None => plné rohové značky (čtverce se zaoblením modulů)
+    #[cfg_attr(feature = "serde", serde(with = "ec_level_serde"))]
+    pub ec_level: EcLevel,
+    pub print_size_mm: f32, // fyzická velikost QR v PDF (včetně tiché zóny)
+    pub dpi: Option<u32>,   // Some => rastr odvozený z `print_size_mm` (místo `size_px`) a DPI v souboru
+    pub quiet_zone_mod: u32, // světlý okraj kolem symbolu v modulech (0–10, doporučeno aspoň 4)
+}
+
+impl Default for QrStyle {
+    fn default() -> Self {
+        Self {
+            size_px: 160,
+            module_rgb: (0, 0, 0),
+            gradient: None,
+            background_rgb: None,
+            alpha_percent: 85,
+            rounding_percent: 0,
+            module_shape: ModuleShape::Square,
+            eyes: None,
+            ec_level: EcLevel::M,
+            print_size_mm: 50.0,
+            quiet_zone_mod: 4,
+            dpi: None,
+        }
+    }
+}
+
+impl QrStyle {
+    /// Tichá zóna omezená na 0–10 modulů.
+    pub(crate) fn quiet_zone(&self) -> u32 {
+        self.quiet_zone_mod.min(10)
+    }
+
+    /// Strana rastru v px pro symbol o `width_mod` modulech (bez tiché zóny). S `dpi` je to
+    /// `print_size_mm` při tomto rozlišení, zaokrouhlené na celý počet pixelů na modul
+    /// (aspoň 1, celkem nejvýš `MAX_PRINT_PX`); jinak `size_px`.
+    pub fn raster_px(&self, width_mod: u32) -> u32 {
+        let Some(dpi) = self.dpi else {
+            return self.size_px;
+        };
+        let total = width_mod + 2 * self.quiet_zone();
+        let target = self.print_size_mm / 25.4 * dpi.max(1) as f32;
+        let module_px = ((target / total as f32).round() as u32).clamp(1, (MAX_PRINT_PX / total).max(1));
+        module_px * total
+    }
+
+    /// Všechna upozornění ke stylu (kontrast, tichá zóna); export nezastavují.
+    pub fn warnings(&self) -> Vec<String> {
+        [self.contrast_warning(), self.quiet_zone_warning()].into_iter().flatten().collect()
+    }
+
+    /// Pod 2 moduly tiché zóny čtečky často symbol nenajdou (hlavně na rušivém pozadí).
+    pub fn quiet_zone_warning(&self) -> Option<String> {
+        (self.quiet_zone() < 2).then(|| {
+            format!("tichá zóna {} mod. je příliš úzká – QR nemusí jít přečíst (doporučeno 4)", self.quiet_zone())
+        })
+    }
+
+    /// Upozornění, když je některá barva modulů (u přechodu kterákoli zastávka, u vlastních
+    /// rohových značek i jejich barvy) příliš světlá proti pozadí. Průhledné pozadí se
+    /// posuzuje jako bílý papír. Mezi zastávkami se jas mění lineárně, stačí tedy zastávky.
+    pub fn contrast_warning(&self) -> Option<String> {
+        let luma = |(r, g, b): (u8, u8, u8)| (0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32) / 255.0;
+        let bg = luma(self.background_rgb.unwrap_or((255, 255, 255)));
+        let mut colors = match &self.gradient {
+            Some(g) => g.stops.iter().map(|s| s.rgb).collect(),
+            None => vec![self.module_rgb],
+        };
+        if let Some(e) = &self.eyes {
+            colors.extend([e.frame_rgb, e.pupil_rgb]);
+        }
+        let lightest = colors.into_iter().max_by(|a, b| luma(*a).total_cmp(&luma(*b)))?;
+        let contrast = bg - luma(lightest);
+        (contrast < MIN_CONTRAST).then(|| {
+            let (r, g, b) = lightest;
+            format!(
+                "barva #{r:02x}{g:02x}{b:02x} má proti pozadí jen {:.0} % kontrastu – QR nemusí jít přečíst",
+                contrast.max(0.0) * 100.0
+            )
+        })
+    }
+}
+
+/// Největší strana rastru odvozeného z fyzické velikosti (paměť pro převzorkování).
+const MAX_PRINT_PX: u32 = 4096;
+
+/// Nejmenší rozdíl jasu tmavých a světlých modulů (0–1); pod ním styl varuje a ověření selže.
+pub(crate) const MIN_CONTRAST: f32 = 0.2;
+
+/// Barevný přechod modulů přes celý symbol (bez tiché zóny).
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Gradient {
+    pub kind: GradientKind,
+    pub stops: Vec<GradientStop>, // aspoň dvě; pořadí nevadí, řadí se podle `offset`
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+pub enum GradientKind {
+    Linear { angle_deg: f32 }, // 0° = zleva doprava, 90° = shora dolů
+    Radial,                    // od středu symbolu k rohům
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GradientStop {
+    pub offset: f32, // 0–1 podél přechodu
+    pub rgb: (u8, u8, u8),
+}
+
+impl Gradient {
+    /// Rovnoměrně rozložené zastávky z barev.
+    pub fn even(kind: GradientKind, colors: &[(u8, u8, u8)]) -> Self {
+        let last = colors.len().saturating_sub(1).max(1) as f32;
+        let stops = colors.iter().enumerate().map(|(i, &rgb)| GradientStop { offset: i as f32 / last, rgb }).collect();
+        Self { kind, stops }
+    }
+
+    /// Zastávky seřazené podle `offset` a omezené na 0–1.
+    pub(crate) fn sorted_stops(&self) -> Vec<GradientStop> {
+        let mut stops: Vec<GradientStop> = self
+            .stops
+            .iter()
+            .map(|s| GradientStop { offset: s.offset.clamp(0.0, 1.0), ..*s })
+            .collect();
+        stops.sort_by(|a, b| a.offset.total_cmp(&b.offset));
+        stops
+    }
+
+    /// Barva v místě `t` (0–1) – lineárně mezi sousedními zastávkami.
+    pub fn color_at(&self, t: f32) -> (u8, u8, u8) {
+        let stops = self.sorted_stops();
+        let (Some(first), Some(last)) = (stops.first(), stops.last()) else {
+            return (0, 0, 0);
+        };
+        if t <= first.offset {
+            return first.rgb;
+        }
+        for w in stops.windows(2) {
+            let (a, b) = (w[0], w[1]);
+            if t <= b.offset {
+                let k = if b.offset > a.offset { (t - a.offset) / (b.offset - a.offset) } else { 1.0 };
+                let mix = |x: u8, y: u8| (x as f32 + (y as f32 - x as f32) * k).round() as u8;
+                return (mix(a.rgb.0, b.rgb.0), mix(a.rgb.1, b.rgb.1), mix(a.rgb.2, b.rgb.2));
+            }
+        }
+        last.rgb
+    }
+
+    /// Geometrie přechodu v souřadnicích modulů pro symbol šířky `n`:
+    /// lineární – začátek a konec osy; kruhový – střed a (střed, poloměr).
+    pub(crate) fn geometry(&self, n: f32) -> ((f32, f32), (f32, f32)) {
+        let c = n / 2.0;
+        match self.kind {
+            GradientKind::Linear { angle_deg } => {
+                let (sin, cos) = angle_deg.to_radians().sin_cos();
+                // osa prochází středem a končí na průmětu nejvzdálenějšího rohu
+                let half = (cos.abs() + sin.abs()) * c;
+                ((c - cos * half, c - sin * half), (c + cos * half, c + sin * half))
+            }
+            GradientKind::Radial => ((c, c), (c * std::f32::consts::SQRT_2, 0.0)),
+        }
+    }
+
+    /// Místo na přechodu (0–1) pro bod `(x, y)` v modulech.
+    pub(crate) fn position(&self, x: f32, y: f32, n: f32) -> f32 {
+        let ((ax, ay), (bx, by)) = self.geometry(n);
+        let t = match self.kind {
+            GradientKind::Linear { .. } => {
+                let (dx, dy) = (bx - ax, by - ay);
+                ((x - ax) * dx + (y - ay) * dy) / (dx * dx + dy * dy).max(f32::EPSILON)
+            }
+            GradientKind::Radial => ((x - ax).powi(2) + (y - ay).powi(2)).sqrt() / bx,
+        };
+        t.clamp(0.0, 1.0)
+    }
+}
+
+/// Tvar tmavých modulů.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum ModuleShape {
+    #[default]
+    Square, // čtverec, rohy podle `rounding_percent`
+    Dot,
+    Diamond,
+    VerticalBars,   // svisle sousedící moduly spojené do pruhu
+    HorizontalBars,
+    Liquid, // sousední moduly splývají, volné rohy jsou oblé
+}
+
+/// Tvar rámečku nebo zornice oka (rohové značky).
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+pub enum EyeShape {
+    #[default]
+    Square,
+    Rounded,
+    Circle,
+    Leaf, // dva protilehlé rohy oblé, dva ostré
+}
+
+/// Vlastní vzhled tří rohových značek (hledacích vzorů); časování a zarovnávací
+/// značky se kreslí dál jako ostatní moduly.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct EyeStyle {
+    pub frame: EyeShape, // vnější rámeček 7×7
+    pub pupil: EyeShape, // vnitřní čtverec 3×3
+    pub frame_rgb: (u8, u8, u8),
+    pub pupil_rgb: (u8, u8, u8),
+}
+
+/// Barva ve tvaru `#RRGGBB` (mřížka nepovinná).
+pub fn parse_rgb(s: &str) -> Option<(u8, u8, u8)> {
+    let hex = s.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let c = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some((c(0)?, c(2)?, c(4)?))
+}
+
+/// Logo vložené doprostřed QR kódu.
+pub struct Logo {
+    pub image: RgbaImage,
+    pub size_percent: u8, // 10–30 % šířky symbolu (bez tiché zóny)
+    pub knockout: bool,   // vyčistit moduly pod logem (+1 modul okraj)
+}
+
+impl Logo {
+    /// Načte logo ze souboru.
+    pub fn open(path: &Path, size_percent: u8, knockout: bool) -> Result<Self> {
+        let image = image::open(path)
+            .map_err(|source| Error::OpenLogo { path: path.to_path_buf(), source })?
+            .to_rgba8();
+        Ok(Self { image, size_percent, knockout })
+    }
+}
+
+/// Načte logo ze souboru (pokud je zadané).
+pub fn load_logo(path: Option<&Path>, size_percent: u8, knockout: bool) -> Result<Option<Logo>> {
+    path.map(|p| Logo::open(p, size_percent, knockout)).transpose()
+}
+
+/// Povolená velikost loga v % šířky symbolu.
+pub(crate) const LOGO_SIZE_PERCENT: std::ops::RangeInclusive<u8> = 10..=30;
+
+/// Velikost loga, jak se skutečně vykreslí (omezená na [`LOGO_SIZE_PERCENT`]).
+pub(crate) fn logo_size_percent(size_percent: u8) -> u8 {
+    size_percent.clamp(*LOGO_SIZE_PERCENT.start(), *LOGO_SIZE_PERCENT.end())
+}
+
+/// Minimální korekce chyb, aby QR s logem šel přečíst.
+/// Zakrytá plocha (logo + případný okraj) má zabrat nejvýš třetinu opravitelné kapacity.
+pub fn min_ec_for_logo(size_percent: u8, knockout: bool) -> EcLevel {
+    let side = logo_size_percent(size_percent) as f32 / 100.0 + if knockout { 0.04 } else { 0.0 };
+    let covered = side * side * 100.0; // % plochy symbolu
+    if covered <= 2.3 {
+        EcLevel::L
+    } else if covered <= 5.0 {
+        EcLevel::M
+    } else if covered <= 8.3 {
+        EcLevel::Q
+    } else {
+        EcLevel::H
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+pub enum OutputFormat {
+    Png,
+    Jpeg,
+    Tiff,
+    Svg,
+    Pdf,
+}
+
+impl OutputFormat {
+    pub const ALL: [OutputFormat; 5] = [
+        OutputFormat::Png,
+        OutputFormat::Jpeg,
+        OutputFormat::Tiff,
+        OutputFormat::Svg,
+        OutputFormat::Pdf,
+    ];
+
+    pub fn ext(self) -> &'static str {
+        match self {
+            OutputFormat::Png => "png",
+            OutputFormat::Jpeg => "jpg",
+            OutputFormat::Tiff => "tif",
+            OutputFormat::Svg => "svg",
+            OutputFormat::Pdf => "pdf",
+        }
+    }
+
+    pub fn from_path(p: &Path) -> Option<Self> {
+        Self::from_ext(p.extension()?.to_str()?)
+    }
+
+    pub fn from_ext(ext: &str) -> Option<Self> {
+        match ext.to_ascii_lowercase().as_str() {
+            "png" => Some(OutputFormat::Png),
+            "jpg" | "jpeg" => Some(OutputFormat::Jpeg),
+            "tif" | "tiff" => Some(OutputFormat::Tiff),
+            "svg" => Some(OutputFormat::Svg),
+            "pdf" => Some(OutputFormat::Pdf),
+            _ => None,
+        }
+    }
+}
+
+/// `EcLevel` jako "L"/"M"/"Q"/"H" – pro `#[serde(with = "kju_ar::ec_level_serde")]`.
+#[cfg(feature = "serde")]
+pub mod ec_level_serde {
+    use qrcode::EcLevel;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(ec: &EcLevel, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(match ec {
+            EcLevel::L => "L",
+            EcLevel::M => "M",
+            EcLevel::Q => "Q",
+            EcLevel::H => "H",
+        })
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<EcLevel, D::Error> {
+        match String::deserialize(d)?.to_ascii_uppercase().as_str() {
+            "L" => Ok(EcLevel::L),
+            "M" => Ok(EcLevel::M),
+            "Q" => Ok(EcLevel::Q),
+            "H" => Ok(EcLevel::H),
+            other => Err(serde::de::Error::custom(format!("neznámá úroveň korekce „{other}“ (L, M, Q, H)"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const N: f32 = 10.0;
+
+    #[test]
+    fn ec_level_for_logo_size() {
+        for (size, knockout, ec) in [
+            (10, false, EcLevel::L),
+            (10, true, EcLevel::L),
+            (20, false, EcLevel::M),
+            (20, true, EcLevel::Q),
+            (30, false, EcLevel::H),
+            (30, true, EcLevel::H),
+            // mimo rozsah jako skutečně vykreslené logo (10–30 %)
+            (0, false, EcLevel::L),
+            (5, true, EcLevel::L),
+            (50, false, EcLevel::H),
+        ] {
+            assert_eq!(min_ec_for_logo(size, knockout), ec, "{size} % knockout={knockout}");
+        }
+        assert_eq!(logo_size_percent(5), 10);
+        assert_eq!(logo_size_percent(25), 25);
+        assert_eq!(logo_size_percent(80), 30);
+    }
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-4
+    }
+
+    #[test]
+    fn linear_gradient_position() {
+        let horizontal = Gradient::even(GradientKind::Linear { angle_deg: 0.0 }, &[(0, 0, 0), (255, 255, 255)]);
+        for (x, y, t) in [(0.0, 0.0, 0.0), (0.0, 10.0, 0.0), (2.5, 3.0, 0.25), (5.0, 9.0, 0.5), (10.0, 0.0, 1.0)] {
+            assert!(close(horizontal.position(x, y, N), t), "0° ({x}, {y})");
+        }
+        let vertical = Gradient { kind: GradientKind::Linear { angle_deg: 90.0 }, ..horizontal.clone() };
+        for (x, y, t) in [(0.0, 0.0, 0.0), (10.0, 0.0, 0.0), (3.0, 7.5, 0.75), (9.0, 10.0, 1.0)] {
+            assert!(close(vertical.position(x, y, N), t), "90° ({x}, {y})");
+        }
+        // mimo osu se místo omezí na 0–1
+        assert_eq!(horizontal.position(-3.0, 5.0, N), 0.0);
+        assert_eq!(horizontal.position(13.0, 5.0, N), 1.0);
+    }
+
+    #[test]
+    fn radial_gradient_position() {
+        let radial = Gradient::even(GradientKind::Radial, &[(0, 0, 0), (255, 255, 255)]);
+        assert!(close(radial.position(5.0, 5.0, N), 0.0));
+        for (x, y) in [(0.0, 0.0), (10.0, 0.0), (0.0, 10.0), (10.0, 10.0)] {
+            assert!(close(radial.position(x, y, N), 1.0), "roh ({x}, {y})");
+        }
+        assert!(close(radial.position(2.5, 2.5, N), 0.5));
+        assert!(close(radial.position(5.0, 0.0, N), std::f32::consts::FRAC_1_SQRT_2));
+    }
+
+    #[test]
+    fn color_between_stops() {
+        let (red, green, blue) = ((255, 0, 0), (0, 255, 0), (0, 0, 255));
+        let stop = |offset, rgb| GradientStop { offset, rgb };
+        // neseřazené zastávky, jedna mimo rozsah 0–1
+        let g = Gradient {
+            kind: GradientKind::Radial,
+            stops: vec![stop(1.5, blue), stop(0.0, red), stop(0.5, green)],
+        };
+        for (t, rgb) in [
+            (-1.0, red),
+            (0.0, red),
+            (0.25, (128, 128, 0)),
+            (0.5, green),
+            (0.75, (0, 128, 128)),
+            (1.0, blue),
+            (2.0, blue),
+        ] {
+            assert_eq!(g.color_at(t), rgb, "t = {t}");
+        }
+        let even = Gradient::even(GradientKind::Radial, &[red, green, blue, (0, 0, 0)]);
+        assert_eq!(even.stops.iter().map(|s| s.offset).collect::<Vec<_>>(), [0.0, 1.0 / 3.0, 2.0 / 3.0, 1.0]);
+        assert_eq!(even.color_at(1.0 / 3.0), green);
+    }
+
+    #[test]
+    fn gradient_contrast_warning() {
+        let style = |stops: &[(u8, u8, u8)], bg| QrStyle {
+            gradient: Some(Gradient::even(GradientKind::Linear { angle_deg: 45.0 }, stops)),
+            background_rgb: bg,
+            ..QrStyle::default()
+        };
+        assert_eq!(style(&[(0, 0, 0), (90, 40, 160)], Some((255, 255, 255))).contrast_warning(), None);
+        // nejsvětlejší zastávka, i když je uprostřed
+        let warning = style(&[(0, 0, 0), (230, 230, 230), (0, 0, 90)], Some((255, 255, 255))).contrast_warning();
+        assert!(warning.is_some_and(|w| w.contains("#e6e6e6")));
+        // průhledné pozadí = bílý papír
+        assert!(style(&[(0, 0, 0), (220, 220, 220)], None).contrast_warning().is_some());
+        // tmavé pozadí a světlé moduly
+        assert!(style(&[(255, 255, 255), (200, 200, 200)], Some((40, 40, 40))).contrast_warning().is_some());
+        // hranice MIN_CONTRAST proti bílé: jas 0,796 (kontrast 20,4 %) projde, 0,804 už ne
+        assert!(style(&[(0, 0, 0), (203, 203, 203)], None).contrast_warning().is_none());
+        assert!(style(&[(0, 0, 0), (205, 205, 205)], None).contrast_warning().is_some());
+    }
+}
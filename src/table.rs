@@ -0,0 +1,252 @@
+//! Tabulka pro hromadný export (CSV/TSV) a mapování jejích sloupců na položky.
+
+use crate::error::{Error, Result};
+use crate::export::BulkItem;
+use crate::style::parse_rgb;
+use crate::utm::Utm;
+use std::path::Path;
+
+/// Načtená tabulka – všechny řádky včetně případného záhlaví.
+#[derive(Clone, Debug, Default)]
+pub struct Table {
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Načte CSV/TSV; oddělovač (tabulátor, středník, čárka) se odhadne z prvního řádku.
+    pub fn open(path: &Path) -> Result<Self> {
+        let data = std::fs::read(path).map_err(Error::io(path))?;
+        Self::parse(&data).map_err(|source| Error::ReadTable { path: path.to_path_buf(), source })
+    }
+
+    pub fn parse(data: &[u8]) -> std::result::Result<Self, csv::Error> {
+        let data = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data); // BOM z Excelu
+        let mut rdr = csv::ReaderBuilder::new()
+            .delimiter(guess_delimiter(data))
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(data);
+        let mut rows = Vec::new();
+        for rec in rdr.records() {
+            let row: Vec<String> = rec?.iter().map(|c| c.trim().to_string()).collect();
+            if row.iter().any(|c| !c.is_empty()) {
+                rows.push(row);
+            }
+        }
+        Ok(Self { rows })
+    }
+
+    pub fn columns(&self) -> usize {
+        self.rows.iter().map(Vec::len).max().unwrap_or(0)
+    }
+
+    /// Názvy sloupců – ze záhlaví, jinak „Sloupec N“.
+    pub fn headers(&self, has_header: bool) -> Vec<String> {
+        (0..self.columns())
+            .map(|i| {
+                let h = if has_header { self.cell(0, i) } else { "" };
+                if h.is_empty() { format!("Sloupec {}", i + 1) } else { h.to_string() }
+            })
+            .collect()
+    }
+
+    fn cell(&self, row: usize, col: usize) -> &str {
+        self.rows.get(row).and_then(|r| r.get(col)).map(String::as_str).unwrap_or("")
+    }
+}
+
+fn guess_delimiter(data: &[u8]) -> u8 {
+    let first = data.split(|&b| b == b'\n').next().unwrap_or(data);
+    let count = |d: u8| first.iter().filter(|&&b| b == d).count();
+    [b'\t', b';', b','].into_iter().max_by_key(|&d| count(d)).filter(|&d| count(d) > 0).unwrap_or(b',')
+}
+
+/// Který sloupec tabulky nese kterou hodnotu (`None` = nepoužívá se).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ColumnMapping {
+    pub has_header: bool,
+    pub payload: usize,
+    pub file_name: Option<usize>,
+    pub module_color: Option<usize>,
+    pub background_color: Option<usize>,
+    pub size: Option<usize>,
+    pub caption: Option<usize>,
+    pub utm: [Option<usize>; 5], // pořadí jako `Utm::KEYS`
+}
+
+impl ColumnMapping {
+    /// Odhad podle záhlaví (české i anglické názvy); bez záhlaví 1. sloupec = obsah, 2. = název souboru.
+    pub fn guess(table: &Table) -> Self {
+        let first: Vec<String> = table.rows.first().map(|r| r.iter().map(|c| c.to_lowercase()).collect()).unwrap_or_default();
+        let find = |names: &[&str]| first.iter().position(|h| names.contains(&h.as_str()));
+
+        let payload = find(&["url", "link", "odkaz", "payload", "obsah", "text", "data"]);
+        let file_name = find(&["file", "filename", "file_name", "soubor", "název souboru", "nazev souboru", "name", "název"]);
+        let module_color = find(&["color", "colour", "barva", "module_color", "barva modulů"]);
+        let background_color = find(&["background", "bg", "pozadí", "pozadi"]);
+        let size = find(&["size", "size_px", "velikost", "px"]);
+        let caption = find(&["caption", "label", "popisek", "title"]);
+        let utm = [
+            find(&["utm_source", "source", "zdroj"]),
+            find(&["utm_medium", "medium", "médium"]),
+            find(&["utm_campaign", "campaign", "kampaň", "kampan"]),
+            find(&["utm_term", "term"]),
+            find(&["utm_content", "content"]),
+        ];
+
+        let has_header = [payload, file_name, module_color, background_color, size, caption]
+            .iter()
+            .chain(&utm)
+            .any(Option::is_some);
+        if !has_header {
+            return Self {
+                has_header: false,
+                payload: 0,
+                file_name: (table.columns() > 1).then_some(1),
+                module_color: None,
+                background_color: None,
+                size: None,
+                caption: None,
+                utm: [None; 5],
+            };
+        }
+        Self { has_header, payload: payload.unwrap_or(0), file_name, module_color, background_color, size, caption, utm }
+    }
+
+    /// Převede datové řádky na položky; prázdný obsah řádek přeskočí.
+    pub fn items(&self, table: &Table) -> Result<Vec<BulkItem>> {
+        let skip = usize::from(self.has_header);
+        let mut items = Vec::new();
+        for (r, row) in table.rows.iter().enumerate().skip(skip) {
+            let get = |c: Option<usize>| c.and_then(|c| row.get(c)).map(|s| s.trim()).filter(|s| !s.is_empty());
+            let Some(payload) = get(Some(self.payload)) else { continue };
+            let bad = |what: &str, v: &str| Error::TableRow { row: r + 1, message: format!("neplatná {what} „{v}“") };
+            let color = |c| get(c).map(|v| parse_rgb(v).ok_or_else(|| bad("barva", v))).transpose();
+            let size = get(self.size)
+                .map(|v| {
+                    v.trim_end_matches("px")
+                        .trim()
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|n| (64..=2048).contains(n))
+                        .ok_or_else(|| bad("velikost (64–2048 px)", v))
+                })
+                .transpose()?;
+            items.push(BulkItem {
+                payload: payload.to_string(),
+                file_name: get(self.file_name).map(str::to_string),
+                module_rgb: color(self.module_color)?,
+                background_rgb: color(self.background_color)?,
+                size_px: size,
+                caption: get(self.caption).map(str::to_string),
+                utm: {
+                    let [source, medium, campaign, term, content] = self.utm.map(|c| get(c).unwrap_or("").to_string());
+                    Utm { source, medium, campaign, term, content }
+                },
+            });
+        }
+        if items.is_empty() {
+            return Err(Error::NoInput);
+        }
+        Ok(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(s: &str) -> Table {
+        Table::parse(s.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn delimiter_from_first_line() {
+        assert_eq!(guess_delimiter(b"url,file\na;b"), b',');
+        assert_eq!(guess_delimiter(b"url;file;color\n"), b';');
+        assert_eq!(guess_delimiter(b"url\tfile\tnote, comma\n"), b'\t');
+        assert_eq!(guess_delimiter(b"https://example.com\n"), b',');
+    }
+
+    #[test]
+    fn csv_and_tsv_give_same_rows() {
+        let csv = table("url,file\nhttps://a.cz,a\n\n https://b.cz , b \n");
+        let tsv = table("url\tfile\nhttps://a.cz\ta\n\nhttps://b.cz\tb\n");
+        assert_eq!(csv.rows, tsv.rows);
+        assert_eq!(csv.rows, [["url", "file"], ["https://a.cz", "a"], ["https://b.cz", "b"]]);
+    }
+
+    #[test]
+    fn bom_is_stripped() {
+        let t = Table::parse(b"\xEF\xBB\xBFurl;soubor\nhttps://a.cz;a\n").unwrap();
+        assert_eq!(t.rows[0][0], "url");
+        let m = ColumnMapping::guess(&t);
+        assert!(m.has_header);
+        assert_eq!((m.payload, m.file_name), (0, Some(1)));
+    }
+
+    #[test]
+    fn guess_by_header_names() {
+        let t = table("Soubor,Odkaz,Barva,Pozadí,Velikost,Popisek,utm_source,Kampaň\n");
+        let m = ColumnMapping::guess(&t);
+        assert!(m.has_header);
+        assert_eq!(m.payload, 1);
+        assert_eq!(m.file_name, Some(0));
+        assert_eq!(m.module_color, Some(2));
+        assert_eq!(m.background_color, Some(3));
+        assert_eq!(m.size, Some(4));
+        assert_eq!(m.caption, Some(5));
+        assert_eq!(m.utm, [Some(6), None, Some(7), None, None]);
+    }
+
+    #[test]
+    fn guess_without_header() {
+        let m = ColumnMapping::guess(&table("https://a.cz,a\nhttps://b.cz,b\n"));
+        assert!(!m.has_header);
+        assert_eq!((m.payload, m.file_name), (0, Some(1)));
+        let m = ColumnMapping::guess(&table("https://a.cz\n"));
+        assert_eq!(m.file_name, None);
+    }
+
+    #[test]
+    fn items_with_overrides() {
+        let t = table("url,color,background,size,caption,utm_source\nhttps://a.cz,#FF0000,#ffffff,512px,Ahoj,letak\n,#000000\nhttps://b.cz\n");
+        let items = ColumnMapping::guess(&t).items(&t).unwrap();
+        assert_eq!(items.len(), 2); // řádek bez obsahu se přeskočí
+        let a = &items[0];
+        assert_eq!(a.payload, "https://a.cz");
+        assert_eq!(a.module_rgb, Some((255, 0, 0)));
+        assert_eq!(a.background_rgb, Some((255, 255, 255)));
+        assert_eq!(a.size_px, Some(512));
+        assert_eq!(a.caption.as_deref(), Some("Ahoj"));
+        assert_eq!(a.utm.source, "letak");
+        let b = &items[1];
+        assert_eq!((b.module_rgb, b.size_px, b.caption.as_deref()), (None, None, None));
+    }
+
+    #[test]
+    fn size_limits() {
+        for (size, ok) in [("64", true), ("2048", true), ("63", false), ("2049", false), ("velký", false)] {
+            let t = table(&format!("url,size\nhttps://a.cz,{size}\n"));
+            assert_eq!(ColumnMapping::guess(&t).items(&t).is_ok(), ok, "{size}");
+        }
+    }
+
+    #[test]
+    fn bad_row_reports_line_number() {
+        let t = table("url;color\nhttps://a.cz;#000000\nhttps://b.cz;červená\n");
+        match ColumnMapping::guess(&t).items(&t) {
+            Err(Error::TableRow { row, message }) => {
+                assert_eq!(row, 3);
+                assert!(message.contains("červená"), "{message}");
+            }
+            other => panic!("čekal jsem chybu řádku, je {other:?}"),
+        }
+    }
+
+    #[test]
+    fn empty_table_is_no_input() {
+        let t = table("url\n");
+        assert!(matches!(ColumnMapping::guess(&t).items(&t), Err(Error::NoInput)));
+    }
+}
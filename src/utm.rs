@@ -0,0 +1,124 @@
+//! Parametry kampaní (UTM) připojené k odkazu v QR.
+
+use crate::payload::URI_VALUE;
+use percent_encoding::utf8_percent_encode;
+
+/// Hodnoty `utm_*`; prázdné pole se do odkazu nepřidá.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct Utm {
+    pub source: String,
+    pub medium: String,
+    pub campaign: String,
+    pub term: String,
+    pub content: String,
+}
+
+impl Utm {
+    /// Názvy parametrů ve stejném pořadí jako [`Utm::values`].
+    pub const KEYS: [&'static str; 5] = ["utm_source", "utm_medium", "utm_campaign", "utm_term", "utm_content"];
+
+    pub fn values(&self) -> [&str; 5] {
+        [&self.source, &self.medium, &self.campaign, &self.term, &self.content].map(|v| v.trim())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values().iter().all(|v| v.is_empty())
+    }
+
+    /// Hodnoty této položky, prázdné doplněné ze společných `base`.
+    pub fn or(&self, base: &Utm) -> Utm {
+        let pick = |own: &String, common: &String| if own.trim().is_empty() { common.clone() } else { own.clone() };
+        Utm {
+            source: pick(&self.source, &base.source),
+            medium: pick(&self.medium, &base.medium),
+            campaign: pick(&self.campaign, &base.campaign),
+            term: pick(&self.term, &base.term),
+            content: pick(&self.content, &base.content),
+        }
+    }
+
+    /// Připojí parametry k odkazu http(s); stávající dotaz i kotva (`#…`) zůstanou,
+    /// stejnojmenné `utm_*` se nahradí. Jiný obsah než odkaz vrátí beze změny.
+    pub fn apply(&self, url: &str) -> String {
+        let url = url.trim();
+        let is_web = ["http://", "https://"]
+            .iter()
+            .any(|p| url.get(..p.len()).is_some_and(|s| s.eq_ignore_ascii_case(p)));
+        let params: Vec<(&str, &str)> =
+            Self::KEYS.into_iter().zip(self.values()).filter(|(_, v)| !v.is_empty()).collect();
+        if !is_web || params.is_empty() {
+            return url.to_string();
+        }
+
+        let (rest, fragment) = match url.split_once('#') {
+            Some((r, f)) => (r, Some(f)),
+            None => (url, None),
+        };
+        let (base, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let mut pairs: Vec<String> = query
+            .split('&')
+            .filter(|p| !p.is_empty())
+            .filter(|p| {
+                let key = p.split('=').next().unwrap_or("");
+                !params.iter().any(|(k, _)| key.eq_ignore_ascii_case(k))
+            })
+            .map(str::to_string)
+            .collect();
+        pairs.extend(params.iter().map(|(k, v)| format!("{k}={}", utf8_percent_encode(v, URI_VALUE))));
+
+        let mut out = format!("{base}?{}", pairs.join("&"));
+        if let Some(f) = fragment {
+            out.push('#');
+            out.push_str(f);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utm(source: &str, medium: &str) -> Utm {
+        Utm { source: source.into(), medium: medium.into(), ..Utm::default() }
+    }
+
+    #[test]
+    fn appends_to_plain_link() {
+        assert_eq!(utm("plakat", "print").apply("https://example.com"), "https://example.com?utm_source=plakat&utm_medium=print");
+        assert_eq!(utm("a b", "").apply(" HTTP://example.com/x "), "HTTP://example.com/x?utm_source=a%20b");
+    }
+
+    #[test]
+    fn keeps_query_and_fragment() {
+        assert_eq!(
+            utm("plakat", "").apply("https://example.com/p?id=5&lang=cs#cena"),
+            "https://example.com/p?id=5&lang=cs&utm_source=plakat#cena"
+        );
+    }
+
+    #[test]
+    fn replaces_existing_keys() {
+        assert_eq!(
+            utm("novy", "").apply("https://example.com/?UTM_SOURCE=stary&utm_medium=web&x=1"),
+            "https://example.com/?utm_medium=web&x=1&utm_source=novy"
+        );
+    }
+
+    #[test]
+    fn skips_non_http_and_empty() {
+        let u = utm("plakat", "print");
+        for s in ["mailto:a@b.cz", "WIFI:S:net;;", "ftp://example.com", "example.com"] {
+            assert_eq!(u.apply(s), s);
+        }
+        assert_eq!(Utm::default().apply("https://example.com?a=1"), "https://example.com?a=1");
+    }
+
+    #[test]
+    fn own_values_override_common() {
+        let common = Utm { campaign: "jaro".into(), ..utm("web", "print") };
+        let merged = utm("letak", " ").or(&common);
+        assert_eq!(merged.values(), ["letak", "print", "jaro", "", ""]);
+    }
+}
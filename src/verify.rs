@@ -0,0 +1,284 @@
+//! Ověření čitelnosti – uložený soubor se načte (SVG a PDF se vykreslí do rastru), QR se
+//! v obraze vyhledá a dekóduje čtečkou `rxing` (port ZXingu) a obsah se porovná se vstupem.
+//!
+//! Poloha ani styl se čtečce nepředávají: symbol se hledá podle vyhledávacích vzorů jako
+//! ve fotografii z telefonu. Ověření tedy selže tam, kde by QR nepřečetla ani běžná čtečka
+//! – poškozený symbol, velké zaoblení, průhlednost, příliš malý QR nebo rušivé pozadí.
+//! Navíc se odmítne nízký kontrast modulů a pozadí, který čtečka sice přečte z čistého
+//! rastru, ale na tisku nebo displeji už ne.
+
+use crate::error::{Error, Result};
+use crate::style::{OutputFormat, MIN_CONTRAST};
+use hayro::hayro_interpret::InterpreterSettings;
+use hayro::hayro_syntax::Pdf;
+use hayro::vello_cpu::color::palette::css::WHITE;
+use hayro::{PixmapSettings, RenderCache, RenderSettings};
+use image::{Rgba, RgbaImage};
+use resvg::{tiny_skia, usvg};
+use std::path::Path;
+
+const VECTOR_PX: u32 = 1200; // delší strana SVG/PDF vykresleného pro ověření
+
+/// Ověří uložený samostatný QR. Rastrové formáty se načtou včetně ztrát JPEG,
+/// SVG a PDF se ze souboru vykreslí do rastru.
+pub fn verify_qr_file(url: &str, path: &Path, fmt: OutputFormat) -> Result<()> {
+    let img = match fmt {
+        OutputFormat::Png | OutputFormat::Jpeg | OutputFormat::Tiff => open_rgba(path)?,
+        OutputFormat::Svg => rasterize_svg(&read(path)?, VECTOR_PX)?,
+        OutputFormat::Pdf => rasterize_pdf(&read(path)?, VECTOR_PX)?,
+    };
+    check(url, &img)
+}
+
+/// Ověří QR vložený do obrázku `in_path`. PDF se vykreslí v rozlišení vstupního obrázku.
+pub fn verify_overlay(url: &str, in_path: &Path, outp: &Path) -> Result<()> {
+    let img = match OutputFormat::from_path(outp) {
+        Some(OutputFormat::Pdf) => {
+            let (w, h) = image::image_dimensions(in_path)
+                .map_err(|source| Error::OpenImage { path: in_path.to_path_buf(), source })?;
+            rasterize_pdf(&read(outp)?, w.max(h))?
+        }
+        _ => open_rgba(outp)?,
+    };
+    check(url, &img)
+}
+
+fn read(path: &Path) -> Result<Vec<u8>> {
+    std::fs::read(path).map_err(Error::io(path))
+}
+
+fn open_rgba(path: &Path) -> Result<RgbaImage> {
+    Ok(image::open(path)
+        .map_err(|source| Error::OpenImage { path: path.to_path_buf(), source })?
+        .to_rgba8())
+}
+
+/// Vykreslí SVG tak, aby delší strana měla `px` pixelů.
+pub(crate) fn rasterize_svg(data: &[u8], px: u32) -> Result<RgbaImage> {
+    let tree = usvg::Tree::from_data(data, &usvg::Options::default())
+        .map_err(|e| Error::Unreadable(format!("SVG nejde načíst ({e})")))?;
+    let size = tree.size();
+    let scale = px as f32 / size.width().max(size.height());
+    let (w, h) = ((size.width() * scale).ceil() as u32, (size.height() * scale).ceil() as u32);
+    let mut pixmap =
+        tiny_skia::Pixmap::new(w.max(1), h.max(1)).ok_or_else(|| Error::Unreadable("SVG má nulovou velikost".into()))?;
+    pixmap.fill(tiny_skia::Color::WHITE);
+    resvg::render(&tree, tiny_skia::Transform::from_scale(scale, scale), &mut pixmap.as_mut());
+    // bílé pozadí → žádná průhlednost, předem vynásobená alfa je totožná s obyčejnou
+    RgbaImage::from_raw(pixmap.width(), pixmap.height(), pixmap.take())
+        .ok_or_else(|| Error::Unreadable("SVG nejde vykreslit".into()))
+}
+
+/// Vykreslí první stránku PDF tak, aby delší strana měla `px` pixelů.
+pub(crate) fn rasterize_pdf(data: &[u8], px: u32) -> Result<RgbaImage> {
+    let pdf = Pdf::new(data.to_vec()).map_err(|e| Error::Unreadable(format!("PDF nejde načíst ({e:?})")))?;
+    let page = pdf.pages().first().ok_or_else(|| Error::Unreadable("PDF nemá žádnou stránku".into()))?;
+    let (w, h) = page.render_dimensions();
+    let scale = px.min(u16::MAX as u32) as f32 / w.max(h);
+    let settings = PixmapSettings { x_scale: scale, y_scale: scale, bg_color: WHITE };
+    let pixmap = hayro::render(
+        page,
+        &RenderCache::new(),
+        &InterpreterSettings::default(),
+        &RenderSettings::default(),
+        &settings,
+    );
+    RgbaImage::from_raw(pixmap.width().into(), pixmap.height().into(), pixmap.data_as_u8_slice().to_vec())
+        .ok_or_else(|| Error::Unreadable("PDF nejde vykreslit".into()))
+}
+
+/// Jas pixelu (0 = černá, 1 = bílá); průhlednost jako na bílém papíře.
+fn luminance(p: &Rgba<u8>) -> f32 {
+    let a = p[3] as f32 / 255.0;
+    let l = (0.299 * p[0] as f32 + 0.587 * p[1] as f32 + 0.114 * p[2] as f32) / 255.0;
+    l * a + (1.0 - a)
+}
+
+/// Vyhledá QR v obraze a ověří, že nese `url` s dostatečným kontrastem.
+fn check(url: &str, img: &RgbaImage) -> Result<()> {
+    let lum: Vec<f32> = img.pixels().map(luminance).collect();
+    // jas roztažený na celý rozsah jako po automatické expozici fotoaparátu; o příliš
+    // nízkém kontrastu rozhoduje až `MIN_CONTRAST` níže
+    let lo = lum.iter().copied().fold(1.0, f32::min);
+    let hi = lum.iter().copied().fold(0.0, f32::max);
+    let span = (hi - lo).max(f32::EPSILON);
+    let luma = lum.iter().map(|l| ((l - lo) / span * 255.0).round() as u8).collect();
+    let found = rxing::helpers::detect_in_luma(luma, img.width(), img.height(), Some(rxing::BarcodeFormat::QR_CODE))
+        .map_err(|e| {
+            Error::Unreadable(match e {
+                rxing::Exceptions::NotFoundException(_) => "QR v obrázku nebyl nalezen".into(),
+                rxing::Exceptions::ChecksumException(_) | rxing::Exceptions::ReedSolomonException(_) => {
+                    "příliš mnoho chybných modulů".into()
+                }
+                other => format!("chyba dekódování ({other})"),
+            })
+        })?;
+    if found.getText() != url {
+        let short: String = found.getText().chars().take(60).collect();
+        return Err(Error::Unreadable(format!("přečteno „{short}“ místo zadaného obsahu")));
+    }
+    let contrast = contrast(&lum, img.width() as usize, found.getPoints());
+    if contrast < MIN_CONTRAST {
+        return Err(Error::Unreadable(format!("nízký kontrast modulů a pozadí ({:.0} %)", contrast.max(0.0) * 100.0)));
+    }
+    Ok(())
+}
+
+/// Rozdíl jasu světlých a tmavých pixelů mezi středy vyhledávacích vzorů; krajní
+/// percentily, aby vyhlazené hrany modulů kontrast nesnižovaly.
+fn contrast(lum: &[f32], width: usize, points: &[rxing::Point]) -> f32 {
+    let height = lum.len() / width.max(1);
+    let xs = points.iter().map(|p| (p.x.max(0.0) as usize).min(width - 1));
+    let ys = points.iter().map(|p| (p.y.max(0.0) as usize).min(height - 1));
+    let (x0, x1) = (xs.clone().min().unwrap_or(0), xs.max().unwrap_or(width - 1));
+    let (y0, y1) = (ys.clone().min().unwrap_or(0), ys.max().unwrap_or(height - 1));
+    let mut symbol: Vec<f32> = (y0..=y1).flat_map(|y| (x0..=x1).map(move |x| lum[y * width + x])).collect();
+    symbol.sort_by(f32::total_cmp);
+    let at = |q: f32| symbol[((symbol.len() - 1) as f32 * q) as usize];
+    at(0.95) - at(0.05)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::caption::Caption;
+    use crate::export::save_qr;
+    use crate::overlay::{overlay_into_image, OverlaySpec};
+    use crate::style::{EyeShape, EyeStyle, ModuleShape, QrStyle};
+    use std::path::PathBuf;
+
+    const URL: &str = "https://example.com/overeni?id=42";
+
+    fn style() -> QrStyle {
+        QrStyle { size_px: 240, background_rgb: Some((255, 255, 255)), ..QrStyle::default() }
+    }
+
+    fn saved(dir: &Path, name: &str, style: &QrStyle, fmt: OutputFormat) -> PathBuf {
+        let path = dir.join(name);
+        save_qr(URL, style, None, None, &path, fmt).unwrap();
+        path
+    }
+
+    #[test]
+    fn accepts_every_module_and_eye_shape() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let modules = [
+            ModuleShape::Square,
+            ModuleShape::Dot,
+            ModuleShape::Diamond,
+            ModuleShape::VerticalBars,
+            ModuleShape::HorizontalBars,
+            ModuleShape::Liquid,
+        ];
+        // výchozí vzhled bez vlastních ok – hledací vzory musí zůstat čitelné u každého tvaru
+        for shape in modules {
+            let style = QrStyle { module_shape: shape, ..style() };
+            let path = saved(dir, &format!("{shape:?}.png"), &style, OutputFormat::Png);
+            verify_qr_file(URL, &path, OutputFormat::Png).unwrap_or_else(|e| panic!("{shape:?}: {e}"));
+        }
+        let eyes = [EyeShape::Square, EyeShape::Rounded, EyeShape::Circle, EyeShape::Leaf];
+        for frame in eyes {
+            for pupil in eyes {
+                let eye = EyeStyle { frame, pupil, frame_rgb: (120, 0, 0), pupil_rgb: (0, 0, 0) };
+                let style = QrStyle { eyes: Some(eye), ..style() };
+                let path = saved(dir, &format!("{frame:?}-{pupil:?}.png"), &style, OutputFormat::Png);
+                verify_qr_file(URL, &path, OutputFormat::Png).unwrap_or_else(|e| panic!("{frame:?}/{pupil:?}: {e}"));
+            }
+        }
+    }
+
+    #[test]
+    fn reads_vector_output_from_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let style = QrStyle { module_shape: ModuleShape::Liquid, ..style() };
+        for fmt in [OutputFormat::Svg, OutputFormat::Pdf, OutputFormat::Jpeg, OutputFormat::Tiff] {
+            let path = saved(dir, &format!("qr.{}", fmt.ext()), &style, fmt);
+            verify_qr_file(URL, &path, fmt).unwrap_or_else(|e| panic!("{fmt:?}: {e}"));
+        }
+        // rámeček QR Platby posouvá symbol od levého horního rohu
+        for fmt in [OutputFormat::Png, OutputFormat::Svg, OutputFormat::Pdf] {
+            let path = dir.join(format!("platba.{}", fmt.ext()));
+            save_qr(URL, &style, None, Some(Caption::PaymentFrame), &path, fmt).unwrap();
+            verify_qr_file(URL, &path, fmt).unwrap_or_else(|e| panic!("rámeček {fmt:?}: {e}"));
+        }
+    }
+
+    #[test]
+    fn rejects_corrupted_image() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let path = saved(dir, "qr.png", &style(), OutputFormat::Png);
+
+        // pás přes střed symbolu přebarvený na bílo – víc chyb, než korekce M opraví
+        let mut img = image::open(&path).unwrap().to_rgba8();
+        let (w, h) = img.dimensions();
+        for y in h * 2 / 5..h * 3 / 5 {
+            for x in w / 5..w * 4 / 5 {
+                img.put_pixel(x, y, Rgba([255, 255, 255, 255]));
+            }
+        }
+        img.save(&path).unwrap();
+        assert!(matches!(verify_qr_file(URL, &path, OutputFormat::Png), Err(Error::Unreadable(_))));
+
+        // useknutý soubor
+        let bytes = std::fs::read(saved(dir, "cely.png", &style(), OutputFormat::Png)).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() / 2]).unwrap();
+        assert!(matches!(verify_qr_file(URL, &path, OutputFormat::Png), Err(Error::OpenImage { .. })));
+
+        let pdf = dir.join("qr.pdf");
+        std::fs::write(&pdf, b"%PDF-1.7\n").unwrap();
+        assert!(matches!(verify_qr_file(URL, &pdf, OutputFormat::Pdf), Err(Error::Unreadable(_))));
+    }
+
+    #[test]
+    fn rejects_low_contrast() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let style = QrStyle { module_rgb: (205, 205, 205), ..style() };
+        let path = saved(dir, "qr.png", &style, OutputFormat::Png);
+        match verify_qr_file(URL, &path, OutputFormat::Png) {
+            Err(Error::Unreadable(msg)) => assert!(msg.contains("kontrast"), "{msg}"),
+            other => panic!("{other:?}"),
+        }
+        // šedé moduly s dostatečným kontrastem projdou
+        let style = QrStyle { module_rgb: (170, 170, 170), ..self::style() };
+        let path = saved(dir, "sedy.png", &style, OutputFormat::Png);
+        verify_qr_file(URL, &path, OutputFormat::Png).unwrap();
+        // průhledné moduly na bílém papíře
+        let style = QrStyle { alpha_percent: 15, ..self::style() };
+        let path = saved(dir, "pruhledny.png", &style, OutputFormat::Png);
+        assert!(verify_qr_file(URL, &path, OutputFormat::Png).is_err());
+    }
+
+    #[test]
+    fn rejects_other_content() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let path = saved(dir, "qr.png", &style(), OutputFormat::Png);
+        match verify_qr_file("https://example.org", &path, OutputFormat::Png) {
+            Err(Error::Unreadable(msg)) => assert!(msg.contains(URL), "{msg}"),
+            other => panic!("{other:?}"),
+        }
+    }
+
+    #[test]
+    fn finds_overlay_in_photo_and_pdf() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        // „fotografie“ se šumem, aby QR nebyl jediná struktura v obraze
+        let photo = RgbaImage::from_fn(900, 600, |x, y| {
+            let v = ((x * 7 + y * 13) % 97 + (x ^ y) % 61) as u8;
+            Rgba([v, 120u8.wrapping_add(v), 200 - v / 2, 255])
+        });
+        let in_path = dir.join("foto.png");
+        photo.save(&in_path).unwrap();
+        let spec = OverlaySpec::default();
+        for name in ["vystup.png", "vystup.jpg", "vystup.pdf"] {
+            let outp = dir.join(name);
+            overlay_into_image(URL, &style(), None, &in_path, spec, &outp).unwrap();
+            verify_overlay(URL, &in_path, &outp).unwrap_or_else(|e| panic!("{name}: {e}"));
+        }
+    }
+}